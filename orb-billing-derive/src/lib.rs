@@ -0,0 +1,286 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE file at the
+// root of this repository, or online at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Derive macros for the [`orb-billing`] crate.
+//!
+//! This crate is an implementation detail of `orb-billing`. Enable the
+//! `derive` feature of `orb-billing` and use the re-exported
+//! `orb_billing::OrbEvent` macro instead of depending on this crate directly.
+//!
+//! [`orb-billing`]: https://docs.rs/orb-billing
+
+#![warn(missing_debug_implementations, missing_docs)]
+
+use std::collections::BTreeSet;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Field, Fields, LitStr};
+
+/// Derives `orb_billing::OrbEvent` for a struct with named fields.
+///
+/// See the documentation of the `orb_billing::OrbEvent` trait for the
+/// supported attributes.
+#[proc_macro_derive(OrbEvent, attributes(orb))]
+pub fn derive_orb_event(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The role that a field plays in an event.
+enum Role {
+    IdempotencyKey,
+    CustomerId,
+    ExternalCustomerId,
+    Timestamp,
+    Property(LitStr),
+    Skip,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    "OrbEvent can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "OrbEvent can only be derived for structs",
+            ))
+        }
+    };
+
+    let event_name = event_name(&input)?;
+
+    let mut idempotency_key = None;
+    let mut customer_id = None;
+    let mut timestamp = None;
+    let mut properties = vec![];
+    let mut property_keys = BTreeSet::new();
+    let mut skipped = vec![];
+    for field in fields {
+        let name = field.ident.as_ref().expect("named field");
+        match field_role(field)? {
+            Role::IdempotencyKey => set_once(&mut idempotency_key, field, "idempotency key", name)?,
+            Role::CustomerId => set_once(&mut customer_id, field, "customer ID", (name, false))?,
+            Role::ExternalCustomerId => {
+                set_once(&mut customer_id, field, "customer ID", (name, true))?
+            }
+            Role::Timestamp => set_once(&mut timestamp, field, "timestamp", name)?,
+            Role::Property(key) => {
+                if !property_keys.insert(key.value()) {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("OrbEvent allows only one property named {:?}", key.value()),
+                    ));
+                }
+                properties.push((name, key, field.ty.span()))
+            }
+            Role::Skip => skipped.push(name),
+        }
+    }
+    let missing = |what: &str| {
+        syn::Error::new_spanned(
+            ident,
+            format!("OrbEvent requires a field marked with {what}"),
+        )
+    };
+    let idempotency_key = idempotency_key.ok_or_else(|| missing("`#[orb(idempotency_key)]`"))?;
+    let (customer_id, external) = customer_id
+        .ok_or_else(|| missing("`#[orb(customer_id)]` or `#[orb(external_customer_id)]`"))?;
+    let timestamp = timestamp.ok_or_else(|| missing("`#[orb(timestamp)]`"))?;
+
+    let into_customer_id = if external {
+        quote!(::orb_billing::OwnedCustomerId::External(
+            ::core::convert::Into::into(event.#customer_id)
+        ))
+    } else {
+        quote!(::orb_billing::OwnedCustomerId::Orb(
+            ::core::convert::Into::into(event.#customer_id)
+        ))
+    };
+    let from_customer_id = if external {
+        quote!(match event.external_customer_id {
+            ::core::option::Option::Some(id) => ::core::convert::Into::into(id),
+            ::core::option::Option::None => {
+                return ::core::result::Result::Err(
+                    ::orb_billing::EventConversionError::MissingExternalCustomerId,
+                );
+            }
+        })
+    } else {
+        quote!(::core::convert::Into::into(event.customer_id))
+    };
+    // Span the trait calls at the field types, so that a type that does not
+    // implement `EventProperty` is reported at the offending field.
+    let into_properties = properties.iter().map(|(name, key, span)| {
+        let value = quote_spanned! {*span=>
+            ::orb_billing::EventProperty::into_property_value(event.#name)
+        };
+        quote! {
+            if let ::core::option::Option::Some(value) = #value {
+                properties.insert(::std::string::String::from(#key), value);
+            }
+        }
+    });
+    let from_properties = properties.iter().map(|(name, key, span)| {
+        let value = quote_spanned! {*span=>
+            ::orb_billing::EventProperty::from_properties(&event.properties, #key)
+        };
+        quote!(#name: #value?,)
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::orb_billing::OrbEvent for #ident #ty_generics #where_clause {
+            const EVENT_NAME: &'static str = #event_name;
+        }
+
+        impl #impl_generics ::core::convert::From<#ident #ty_generics>
+            for ::orb_billing::OwnedIngestEventRequest #where_clause
+        {
+            fn from(event: #ident #ty_generics) -> Self {
+                let mut properties = ::std::collections::BTreeMap::new();
+                #(#into_properties)*
+                ::orb_billing::OwnedIngestEventRequest {
+                    idempotency_key: ::core::convert::Into::into(event.#idempotency_key),
+                    customer_id: #into_customer_id,
+                    event_name: ::std::string::String::from(#event_name),
+                    properties,
+                    timestamp: event.#timestamp,
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<::orb_billing::Event>
+            for #ident #ty_generics #where_clause
+        {
+            type Error = ::orb_billing::EventConversionError;
+
+            fn try_from(
+                event: ::orb_billing::Event,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                if event.event_name != #event_name {
+                    return ::core::result::Result::Err(
+                        ::orb_billing::EventConversionError::EventName {
+                            expected: #event_name,
+                            actual: event.event_name,
+                        },
+                    );
+                }
+                ::core::result::Result::Ok(#ident {
+                    #(#from_properties)*
+                    #(#skipped: ::core::default::Default::default(),)*
+                    #idempotency_key: ::core::convert::Into::into(event.id),
+                    #customer_id: #from_customer_id,
+                    #timestamp: event.timestamp,
+                })
+            }
+        }
+    })
+}
+
+/// Parses the required `#[orb(event_name = "...")]` attribute of the struct.
+fn event_name(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut event_name = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("orb")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("event_name") {
+                let name: LitStr = meta.value()?.parse()?;
+                if name.value().is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        "event name must not be empty",
+                    ));
+                }
+                event_name = Some(name);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported orb attribute; expected `event_name`"))
+            }
+        })?;
+    }
+    event_name.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "OrbEvent requires an `#[orb(event_name = \"...\")]` attribute",
+        )
+    })
+}
+
+/// Determines the role of a field from its `#[orb(...)]` attributes.
+///
+/// Fields without a role attribute are properties named after the field.
+fn field_role(field: &Field) -> syn::Result<Role> {
+    let name = field.ident.as_ref().expect("named field");
+    let mut role = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("orb")) {
+        attr.parse_nested_meta(|meta| {
+            let parsed = if meta.path.is_ident("idempotency_key") {
+                Role::IdempotencyKey
+            } else if meta.path.is_ident("customer_id") {
+                Role::CustomerId
+            } else if meta.path.is_ident("external_customer_id") {
+                Role::ExternalCustomerId
+            } else if meta.path.is_ident("timestamp") {
+                Role::Timestamp
+            } else if meta.path.is_ident("rename") {
+                Role::Property(meta.value()?.parse()?)
+            } else if meta.path.is_ident("skip") {
+                Role::Skip
+            } else {
+                return Err(meta.error(
+                    "unsupported orb attribute; expected one of `idempotency_key`, \
+                     `customer_id`, `external_customer_id`, `timestamp`, `rename`, or `skip`",
+                ));
+            };
+            if role.is_some() {
+                return Err(meta.error("conflicting orb attributes"));
+            }
+            role = Some(parsed);
+            Ok(())
+        })?;
+    }
+    Ok(role.unwrap_or_else(|| {
+        let key = name.to_string();
+        Role::Property(LitStr::new(
+            key.strip_prefix("r#").unwrap_or(&key),
+            name.span(),
+        ))
+    }))
+}
+
+/// Stores `value` in `slot`, or reports a duplicate `what` at `field`.
+fn set_once<T>(slot: &mut Option<T>, field: &Field, what: &str, value: T) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new(
+            field.span(),
+            format!("OrbEvent allows only one {what}"),
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
+}
@@ -13,17 +13,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::pin::{pin, Pin};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
 use async_stream::try_stream;
 use futures_core::Stream;
-use reqwest::{Method, RequestBuilder, Url};
+use futures_util::future::Either;
+use futures_util::StreamExt;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::{Method, StatusCode, Url};
 use serde::de::DeserializeOwned;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc2822;
+use time::OffsetDateTime;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
 
-use crate::config::ListParams;
-use crate::error::ApiError;
+use crate::client::events::EventValidationRules;
+use crate::config::{ListParams, RequestOptions, RetryPolicy};
+use crate::error::{ApiError, ApiErrorKind, DecodeError};
+use crate::limit::Limiter;
+use crate::metrics::{ClientMetrics, IngestMetrics, RequestMetrics, StatusClass};
+use crate::trace::RequestSpan;
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::util::ApiPath;
 use crate::{ClientBuilder, ClientConfig, Error};
 
 pub mod alerts;
+pub mod backfill;
 pub mod coupons;
 pub mod customers;
 pub mod events;
@@ -33,25 +53,53 @@ pub mod plans;
 pub mod prices;
 pub mod subscriptions;
 pub mod taxes;
-pub(crate) mod backfill;
+
+/// The request header that carries an idempotency key.
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// The response header in which Orb reports the ID it assigned to a request.
+const REQUEST_ID_HEADER: &str = "x-request-id";
 
 /// An API client for Orb.
 ///
-/// The API client is designed to be wrapped in an [`Arc`] and used from
-/// multiple threads simultaneously.
-///
-/// [`Arc`]: std::sync::Arc
-#[derive(Debug)]
+/// The API client can be used from multiple threads simultaneously. Cloning
+/// the client is cheap: clones share their connections, rate limits, and
+/// other configuration. Streams returned by the client own a clone of the
+/// client, so they can be moved into spawned tasks.
+#[derive(Debug, Clone)]
 pub struct Client {
-    pub(crate) inner: reqwest::Client,
+    pub(crate) inner: Arc<ClientInner>,
+    pub(crate) options: Arc<RequestOptions>,
+}
+
+/// The state shared by all clones of a [`Client`].
+#[derive(Debug)]
+pub(crate) struct ClientInner {
+    /// Used only to construct requests. Requests are sent via `transport`.
+    pub(crate) reqwest: reqwest::Client,
+    pub(crate) transport: Arc<dyn HttpTransport>,
     pub(crate) api_key: String,
     pub(crate) endpoint: Url,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) limiter: Limiter,
+    pub(crate) ingest_limiter: Option<Limiter>,
+    pub(crate) metrics: Option<Arc<dyn ClientMetrics>>,
+    pub(crate) auto_idempotency_keys: bool,
+    pub(crate) event_validation: Option<EventValidationRules>,
 }
 
 impl Client {
     /// Creates a new `Client` from its required configuration parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the underlying HTTP client cannot be constructed, e.g.,
+    /// because no TLS backend is available. Use [`ClientBuilder::build`] to
+    /// handle this error instead.
     pub fn new(config: ClientConfig) -> Client {
-        ClientBuilder::default().build(config)
+        ClientBuilder::default()
+            .build(config)
+            .expect("default client configuration is valid")
     }
 
     /// Creates a builder for a `Client` that allows for customization of
@@ -60,16 +108,55 @@ impl Client {
         ClientBuilder::default()
     }
 
+    /// Returns a handle to this client that applies the specified options to
+    /// every request made through it.
+    ///
+    /// The handle shares its connections, rate limits, and other
+    /// configuration with this client. If this client is itself a handle
+    /// with options, the options are combined, with the specified options
+    /// taking precedence.
+    ///
+    /// ```no_run
+    /// # async fn example(client: &orb_billing::Client) -> Result<(), orb_billing::Error> {
+    /// use orb_billing::RequestOptions;
+    ///
+    /// let options = RequestOptions::new().idempotency_key("void-inv-123");
+    /// client.with_options(options).void_invoice("inv_123").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_options(&self, options: RequestOptions) -> Client {
+        let mut merged = RequestOptions::clone(&self.options);
+        merged.merge(options);
+        Client {
+            inner: Arc::clone(&self.inner),
+            options: Arc::new(merged),
+        }
+    }
+
     fn build_request<P>(&self, method: Method, path: P) -> RequestBuilder
     where
-        P: IntoIterator,
-        P::Item: AsRef<str>,
+        P: Into<ApiPath>,
     {
-        let mut url = self.endpoint.clone();
+        let path = path.into();
+        let mut url = self.inner.endpoint.clone();
         url.path_segments_mut()
             .expect("builder validated URL can be a base")
-            .extend(path);
-        self.inner.request(method, url).bearer_auth(&self.api_key)
+            .extend(path.segments);
+        let api_key = self.options.api_key.as_ref().unwrap_or(&self.inner.api_key);
+        let mut inner = self
+            .inner
+            .reqwest
+            .request(method, url)
+            .bearer_auth(api_key)
+            .headers(self.options.headers.clone());
+        if let Some(timeout) = self.options.timeout {
+            inner = inner.timeout(timeout);
+        }
+        RequestBuilder {
+            inner,
+            template: path.template,
+        }
     }
 
     async fn send_request<T>(&self, req: RequestBuilder) -> Result<T, Error>
@@ -78,6 +165,8 @@ impl Client {
     {
         #[derive(Deserialize)]
         struct ErrorResponse {
+            #[serde(default, rename = "type")]
+            type_: Option<String>,
             title: String,
             #[serde(default)]
             detail: Option<String>,
@@ -85,68 +174,738 @@ impl Client {
             validation_errors: Vec<String>,
         }
 
-        let res = req.send().await?;
-        let status_code = res.status();
-        if status_code.is_success() {
-            Ok(res.json().await?)
+        let template = req.template;
+        let mut req = HttpRequest::from_reqwest(req.inner.build()?);
+        // An idempotency key set by the request itself takes precedence. The
+        // key is attached once, before any retries, so that every attempt
+        // carries the same key.
+        let generate_key =
+            self.inner.auto_idempotency_keys && matches!(req.method, Method::POST | Method::PUT);
+        if req.method != Method::GET && !req.headers.contains_key(IDEMPOTENCY_KEY_HEADER) {
+            if let Some(key) = &self.options.idempotency_key {
+                let key = HeaderValue::from_str(key).map_err(|_| Error::InvalidConfig {
+                    detail: format!("invalid idempotency key: {key:?}"),
+                })?;
+                req.headers.insert(IDEMPOTENCY_KEY_HEADER, key);
+            } else if generate_key {
+                let key = Uuid::new_v4().to_string();
+                let key = HeaderValue::from_str(&key).expect("UUIDs are valid header values");
+                req.headers.insert(IDEMPOTENCY_KEY_HEADER, key);
+            }
+        }
+        let path = req.url.path().to_string();
+        let res = self.execute(&template, req).await?;
+        if res.status.is_success() {
+            decode(&path, &res)
         } else {
-            let res_body = res.text().await?;
-            match serde_json::from_str::<ErrorResponse>(&res_body) {
-                Ok(e) => Err(Error::Api(ApiError {
-                    status_code,
-                    title: e.title,
-                    detail: e.detail,
-                    validation_errors: e.validation_errors,
-                })),
-                Err(e) => {
-                    eprintln!("There's been an API error! {e:?} from {res_body:?}");
-                    Err(Error::Api(ApiError {
-                        status_code,
-                        title: "decoding failure".into(),
-                        detail: Some("unable to decode API response as JSON".into()),
-                        validation_errors: vec![],
-                    }))
+            let e: ErrorResponse = decode(&path, &res)?;
+            Err(Error::Api(Box::new(ApiError {
+                status_code: res.status,
+                title: e.title,
+                detail: e.detail,
+                validation_errors: e.validation_errors,
+                kind: e.type_.as_deref().map(ApiErrorKind::from_type_uri),
+                retry_after: parse_retry_after(&res.headers),
+                request_id: parse_request_id(&res.headers),
+            })))
+        }
+    }
+
+    /// Executes a request, retrying it according to the client's
+    /// [`RetryPolicy`] if it is safe to do so.
+    ///
+    /// Returns the last response received, whether or not it indicates
+    /// success.
+    async fn execute(&self, template: &str, req: HttpRequest) -> Result<HttpResponse, Error> {
+        let span = RequestSpan::new(&req.method, template);
+        let method = req.method.clone();
+        let request_bytes = req.body.as_ref().map_or(0, |b| b.len());
+        let start = Instant::now();
+        let mut attempts = 0;
+        let res = span
+            .instrument(self.execute_attempts(&span, req, &mut attempts))
+            .await;
+        let duration = start.elapsed();
+        let request_id = res
+            .as_ref()
+            .ok()
+            .and_then(|res| parse_request_id(&res.headers));
+        span.record_result(&res, request_id.as_deref(), duration);
+        if let Some(metrics) = &self.inner.metrics {
+            let status = res.as_ref().ok().map(|res| res.status);
+            metrics.on_request(&RequestMetrics {
+                method: &method,
+                endpoint: template,
+                status_class: status.map_or(StatusClass::TransportError, StatusClass::from_status),
+                status,
+                duration,
+                attempts,
+                request_bytes,
+                response_bytes: res.as_ref().map_or(0, |res| res.body.len()),
+            });
+        }
+        res
+    }
+
+    async fn execute_attempts(
+        &self,
+        span: &RequestSpan,
+        req: HttpRequest,
+        attempts: &mut u32,
+    ) -> Result<HttpResponse, Error> {
+        let policy = self
+            .options
+            .retry_policy
+            .as_ref()
+            .unwrap_or(&self.inner.retry_policy);
+        let limiter = self.limiter_for(&req);
+        let retry_safe =
+            req.method == Method::GET || req.headers.contains_key(IDEMPOTENCY_KEY_HEADER);
+        let mut attempt = 1;
+        loop {
+            *attempts = attempt;
+            let permit = limiter.acquire().await;
+            let attempt_start = Instant::now();
+            let res = self.inner.transport.send(req.clone()).await;
+            drop(permit);
+            span.record_attempt(attempt, &res, attempt_start.elapsed());
+            if !retry_safe || attempt >= policy.max_attempts {
+                return Ok(res?);
+            }
+            let delay = match &res {
+                Ok(res) if is_retryable_status(res.status) => {
+                    policy.delay_for(attempt, parse_retry_after(&res.headers))
+                }
+                Ok(_) => None,
+                Err(e) if e.is_retryable() => Some(policy.backoff(attempt)),
+                Err(_) => None,
+            };
+            match delay {
+                None => return Ok(res?),
+                Some(delay) => {
+                    span.record_retry(attempt, delay);
+                    tokio::time::sleep(delay).await
                 }
             }
+            attempt += 1;
+        }
+    }
+
+    /// Reports the outcome of an event ingestion request to the client's
+    /// metrics observer, if any.
+    pub(crate) fn observe_ingest(&self, ingest: IngestMetrics) {
+        if let Some(metrics) = &self.inner.metrics {
+            metrics.on_ingest(&ingest);
+        }
+    }
+
+    /// Returns the limiter that governs the specified request.
+    fn limiter_for(&self, req: &HttpRequest) -> &Limiter {
+        match &self.inner.ingest_limiter {
+            Some(limiter) if req.url.path().ends_with("/ingest") => limiter,
+            _ => &self.inner.limiter,
         }
     }
 
-    fn stream_paginated_request<'a, T>(
-        &'a self,
+    fn stream_paginated_request<T>(
+        &self,
         params: &ListParams,
         req: RequestBuilder,
-    ) -> impl Stream<Item = Result<T, Error>> + 'a
+    ) -> impl Stream<Item = Result<T, Error>> + 'static
     where
-        T: DeserializeOwned + 'a,
+        T: DeserializeOwned + Send + 'static,
     {
-        #[derive(Deserialize)]
-        struct Paginated<T> {
-            data: Vec<T>,
-            pagination_metadata: PaginationMetadata,
+        let pages = self.stream_paginated_pages(params, req);
+        try_stream! {
+            for await page in pages {
+                for datum in page?.data {
+                    yield datum;
+                }
+            }
         }
+    }
 
-        #[derive(Deserialize)]
-        struct PaginationMetadata {
-            next_cursor: Option<String>,
+    fn stream_paginated_pages<T>(
+        &self,
+        params: &ListParams,
+        req: RequestBuilder,
+    ) -> impl Stream<Item = Result<Page<T>, Error>> + 'static
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let pages = paginate(self.clone(), params, req);
+        if params.prefetch == 0 {
+            Either::Left(pages)
+        } else {
+            // Fetch pages on a separate task, so that fetching proceeds while
+            // the consumer processes earlier pages.
+            Either::Right(Prefetch::spawn(pages, params.prefetch))
         }
+    }
+}
 
-        let req = req.query(&[("limit", params.page_size)]);
-        try_stream! {
-            let mut cursor = None;
-            loop {
-                let mut current_req = req.try_clone().expect("request is clonable");
-                if let Some(cursor) = cursor {
-                    current_req = current_req.query(&[("cursor", cursor)]);
-                }
-                let res: Paginated<T> = self.send_request(current_req).await?;
-                for datum in res.data {
-                    yield datum;
-                }
-                match res.pagination_metadata.next_cursor {
-                    None => break,
-                    Some(next_cursor) => cursor = Some(next_cursor),
+/// Streams the pages of a paginated list operation.
+fn paginate<T>(
+    client: Client,
+    params: &ListParams,
+    req: RequestBuilder,
+) -> impl Stream<Item = Result<Page<T>, Error>> + 'static
+where
+    T: DeserializeOwned + 'static,
+{
+    #[derive(Deserialize)]
+    struct Paginated<T> {
+        data: Vec<T>,
+        pagination_metadata: PaginationMetadata,
+    }
+
+    #[derive(Deserialize)]
+    struct PaginationMetadata {
+        next_cursor: Option<String>,
+    }
+
+    let req = req.query(&[("limit", params.page_size)]);
+    let mut cursor = params.cursor.clone();
+    try_stream! {
+        loop {
+            let mut current_req = req.try_clone().expect("request is clonable");
+            if let Some(cursor) = &cursor {
+                current_req = current_req.query(&[("cursor", cursor)]);
+            }
+            // Retries happen within `send_request`, so a failed page
+            // fetch is retried without restarting from the first page.
+            let res: Paginated<T> = client.send_request(current_req).await?;
+            cursor = res.pagination_metadata.next_cursor;
+            let done = cursor.is_none();
+            yield Page {
+                data: res.data,
+                next_cursor: cursor.clone(),
+            };
+            if done {
+                break;
+            }
+        }
+    }
+}
+
+/// A stream of pages fetched ahead of the consumer by a background task.
+///
+/// The task stops fetching when the consumer falls the configured number of
+/// pages behind, and is aborted when the stream is dropped.
+struct Prefetch<T> {
+    rx: mpsc::Receiver<Result<Page<T>, Error>>,
+    task: JoinHandle<()>,
+}
+
+impl<T> Prefetch<T>
+where
+    T: Send + 'static,
+{
+    fn spawn<S>(pages: S, prefetch: usize) -> Prefetch<T>
+    where
+        S: Stream<Item = Result<Page<T>, Error>> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(prefetch.max(1));
+        let task = tokio::spawn(async move {
+            let mut pages = pin!(pages);
+            // Reserve room in the buffer before fetching a page, so that no
+            // more than `prefetch` pages are fetched ahead of the consumer.
+            while let Ok(permit) = tx.reserve().await {
+                let Some(page) = pages.next().await else {
+                    break;
+                };
+                let failed = page.is_err();
+                permit.send(page);
+                if failed {
+                    break;
                 }
             }
+        });
+        Prefetch { rx, task }
+    }
+}
+
+impl<T> Stream for Prefetch<T> {
+    type Item = Result<Page<T>, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for Prefetch<T> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// A page of results from a paginated list operation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Page<T> {
+    /// The items on the page.
+    pub data: Vec<T>,
+    /// The cursor for the next page, or `None` if this is the last page.
+    ///
+    /// To resume a list operation after this page, pass the cursor to
+    /// [`ListParams::cursor`].
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Transforms the items on the page with `f`, dropping the items for
+    /// which `f` returns `None`.
+    pub(crate) fn try_filter_map<U, F>(self, f: F) -> Result<Page<U>, Error>
+    where
+        F: FnMut(T) -> Result<Option<U>, Error>,
+    {
+        let data = self
+            .data
+            .into_iter()
+            .map(f)
+            .filter_map(Result::transpose)
+            .collect::<Result<_, _>>()?;
+        Ok(Page {
+            data,
+            next_cursor: self.next_cursor,
+        })
+    }
+}
+
+/// A request under construction.
+///
+/// Wraps a [`reqwest::RequestBuilder`] to track the template of the request's
+/// path. See [`ApiPath`].
+#[derive(Debug)]
+pub(crate) struct RequestBuilder {
+    inner: reqwest::RequestBuilder,
+    template: String,
+}
+
+impl RequestBuilder {
+    pub(crate) fn query<T>(mut self, query: &T) -> RequestBuilder
+    where
+        T: Serialize + ?Sized,
+    {
+        self.inner = self.inner.query(query);
+        self
+    }
+
+    pub(crate) fn json<T>(mut self, json: &T) -> RequestBuilder
+    where
+        T: Serialize + ?Sized,
+    {
+        self.inner = self.inner.json(json);
+        self
+    }
+
+    pub(crate) fn header(mut self, key: &'static str, value: &str) -> RequestBuilder {
+        self.inner = self.inner.header(key, value);
+        self
+    }
+
+    fn try_clone(&self) -> Option<RequestBuilder> {
+        Some(RequestBuilder {
+            inner: self.inner.try_clone()?,
+            template: self.template.clone(),
+        })
+    }
+}
+
+/// Decodes a JSON response body, tracking the path to the value that failed
+/// to decode, if any.
+fn decode<T>(path: &str, res: &HttpResponse) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let error = |json_path: String, e| {
+        Error::Decode(Box::new(DecodeError::new(
+            res.status,
+            path.into(),
+            json_path,
+            e,
+            &res.body,
+            parse_request_id(&res.headers),
+        )))
+    };
+    let mut de = serde_json::Deserializer::from_slice(&res.body);
+    let res = serde_path_to_error::deserialize(&mut de)
+        .map_err(|e| error(e.path().to_string(), e.into_inner()))?;
+    de.end().map_err(|e| error(".".into(), e))?;
+    Ok(res)
+}
+
+/// Extracts the ID that Orb assigned to a request from a response's headers.
+fn parse_request_id(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(REQUEST_ID_HEADER)?.to_str().ok()?;
+    Some(value.into())
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Parses the `Retry-After` header, which may contain either a number of
+/// seconds or an HTTP date.
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    let delay = date - OffsetDateTime::now_utc();
+    Some(delay.try_into().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+    use reqwest::header::HeaderName;
+    use serde_json::json;
+
+    use super::*;
+    use crate::error::{TransportError, TransportErrorKind};
+    use crate::transport::mock::{test_event, MockTransport};
+    use crate::util::PathExt;
+    use crate::CustomerId;
+
+    fn retry_after(value: &str) -> Option<Duration> {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        parse_retry_after(&headers)
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds() {
+        assert_eq!(retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(retry_after("bogus"), None);
+        assert_eq!(parse_retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn retry_after_accepts_http_dates() {
+        assert_eq!(
+            retry_after("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(Duration::ZERO)
+        );
+        let future = OffsetDateTime::now_utc() + Duration::from_secs(3600);
+        let delay = retry_after(&future.format(&Rfc2822).unwrap()).unwrap();
+        assert!(delay > Duration::from_secs(3500) && delay <= Duration::from_secs(3600));
+    }
+
+    #[tokio::test]
+    async fn get_requests_are_retried() {
+        let transport = MockTransport::default();
+        transport.respond(503, json!({ "title": "unavailable" }));
+        transport.fail(TransportError::new(TransportErrorKind::Connect, "refused"));
+        transport.respond(200, json!({ "ok": true }));
+        let client = transport.client();
+        let req = client.build_request(Method::GET, ["customers"]);
+        let res: serde_json::Value = client.send_request(req).await.unwrap();
+        assert_eq!(res, json!({ "ok": true }));
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn unsafe_requests_are_not_retried() {
+        let transport = MockTransport::default();
+        transport.respond(503, json!({ "title": "unavailable" }));
+        let client = transport.client();
+        let req = client.build_request(Method::POST, ["customers"]);
+        let res: Result<serde_json::Value, _> = client.send_request(req).await;
+        assert!(matches!(res, Err(Error::Api(e)) if e.status_code == 503));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn requests_with_idempotency_keys_are_retried() {
+        let transport = MockTransport::default();
+        transport.respond(429, json!({ "title": "too many requests" }));
+        transport.respond(200, json!({}));
+        let client = transport.client();
+        let req = client
+            .build_request(Method::POST, ["customers"])
+            .header("Idempotency-Key", "key");
+        let _: serde_json::Value = client.send_request(req).await.unwrap();
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn request_options_apply_to_requests() {
+        let transport = MockTransport::default();
+        transport.respond(500, json!({ "title": "oops" }));
+        transport.respond(200, json!({}));
+        transport.respond(200, json!({}));
+        transport.respond(200, json!({}));
+        let client = transport.client().with_options(
+            RequestOptions::new()
+                .api_key("override")
+                .header(
+                    HeaderName::from_static("x-test"),
+                    HeaderValue::from_static("1"),
+                )
+                .timeout(Duration::from_secs(5)),
+        );
+        let client = client.with_options(RequestOptions::new().idempotency_key("key"));
+
+        let req = client.build_request(Method::POST, ["customers"]);
+        let _: serde_json::Value = client.send_request(req).await.unwrap();
+        let req = client
+            .build_request(Method::POST, ["customers"])
+            .header("Idempotency-Key", "explicit");
+        let _: serde_json::Value = client.send_request(req).await.unwrap();
+        let req = client.build_request(Method::GET, ["customers"]);
+        let _: serde_json::Value = client.send_request(req).await.unwrap();
+
+        let requests = transport.requests();
+        // The failed request was retried because it had an idempotency key.
+        assert_eq!(requests.len(), 4);
+        for req in &requests {
+            assert_eq!(req.headers["authorization"], "Bearer override");
+            assert_eq!(req.headers["x-test"], "1");
+            assert_eq!(req.timeout, Some(Duration::from_secs(5)));
+        }
+        assert_eq!(requests[1].headers["idempotency-key"], "key");
+        assert_eq!(requests[2].headers["idempotency-key"], "explicit");
+        assert!(!requests[3].headers.contains_key("idempotency-key"));
+    }
+
+    #[tokio::test]
+    async fn generated_idempotency_keys_are_reused_across_retries() {
+        let transport = MockTransport::default();
+        transport.respond(503, json!({ "title": "unavailable" }));
+        transport.respond(200, json!({}));
+        transport.respond(200, json!({}));
+        transport.respond(200, json!({}));
+        let client = transport.client_with(|builder| builder.auto_idempotency_keys(true));
+        for method in [Method::POST, Method::POST, Method::GET] {
+            let req = client.build_request(method, ["customers"]);
+            let _: serde_json::Value = client.send_request(req).await.unwrap();
+        }
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 4);
+        let key = &requests[0].headers["idempotency-key"];
+        assert!(Uuid::parse_str(key.to_str().unwrap()).is_ok());
+        assert_eq!(&requests[1].headers["idempotency-key"], key);
+        assert_ne!(&requests[2].headers["idempotency-key"], key);
+        assert!(!requests[3].headers.contains_key("idempotency-key"));
+    }
+
+    #[tokio::test]
+    async fn api_errors_are_typed() {
+        let transport = MockTransport::default();
+        transport.respond(
+            404,
+            json!({
+                "type": "https://docs.withorb.com/reference/error-responses#404-resource-not-found",
+                "status": 404,
+                "title": "Resource not found",
+            }),
+        );
+        let client = transport.client();
+        let err = client.get_customer("noexist").await.unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.request_id(), None);
+        assert!(!err.is_conflict());
+        assert!(!err.is_retryable());
+        match err {
+            Error::Api(e) => assert_eq!(e.kind, Some(ApiErrorKind::ResourceNotFound)),
+            e => panic!("unexpected error: {e}"),
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("req_1"));
+        transport.respond_with(HttpResponse {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            headers,
+            body: serde_json::to_vec(&json!({
+                "type": "https://docs.withorb.com/reference/error-responses#429-too-many-requests",
+                "title": "Too many requests",
+            }))
+            .unwrap(),
+        });
+        let err = client.get_customer("cus_1").await.unwrap_err();
+        assert!(err.is_retryable());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(3600)));
+        assert_eq!(err.request_id(), Some("req_1"));
+    }
+
+    #[tokio::test]
+    async fn metrics_observe_requests_and_ingestion() {
+        #[derive(Debug, Default)]
+        struct Recorder {
+            requests: std::sync::Mutex<Vec<(String, StatusClass, u32)>>,
+            ingests: std::sync::Mutex<Vec<IngestMetrics>>,
+        }
+
+        impl ClientMetrics for Arc<Recorder> {
+            fn on_request(&self, request: &RequestMetrics<'_>) {
+                self.requests.lock().unwrap().push((
+                    request.endpoint.into(),
+                    request.status_class,
+                    request.attempts,
+                ));
+            }
+
+            fn on_ingest(&self, ingest: &IngestMetrics) {
+                self.ingests.lock().unwrap().push(ingest.clone());
+            }
+        }
+
+        let transport = MockTransport::default();
+        transport.respond(500, json!({ "title": "oops" }));
+        transport.respond(404, json!({ "title": "not found" }));
+        transport.respond(
+            200,
+            json!({ "debug": { "duplicate": ["a"], "ingested": [] } }),
+        );
+        let recorder = Arc::new(Recorder::default());
+        let client = transport.client_with(|builder| builder.metrics(recorder.clone()));
+        client.get_customer("cus_1").await.unwrap_err();
+        let event = test_event("a");
+        client
+            .ingest_events(crate::IngestionMode::Debug, None, &[event.as_request()])
+            .await
+            .unwrap();
+        assert_eq!(
+            *recorder.requests.lock().unwrap(),
+            [
+                ("customers/{id}".into(), StatusClass::ClientError, 2),
+                ("ingest".into(), StatusClass::Success, 1),
+            ]
+        );
+        let ingests = recorder.ingests.lock().unwrap();
+        assert_eq!(ingests.len(), 1);
+        assert_eq!(ingests[0].events, 1);
+        assert_eq!(ingests[0].accepted, Some(0));
+        assert_eq!(ingests[0].duplicate, Some(1));
+    }
+
+    #[tokio::test]
+    async fn pagination_reports_and_resumes_from_cursors() {
+        let transport = MockTransport::default();
+        transport.respond(
+            200,
+            json!({ "data": [1, 2], "pagination_metadata": { "next_cursor": "c2" } }),
+        );
+        transport.respond(
+            200,
+            json!({ "data": [3], "pagination_metadata": { "next_cursor": null } }),
+        );
+        let client = transport.client();
+        let params = ListParams::DEFAULT.page_size(2).cursor("c1");
+        let req = client.build_request(Method::GET, ["customers"]);
+        let pages: Vec<Page<u64>> = client
+            .stream_paginated_pages(&params, req)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(
+            pages,
+            [
+                Page {
+                    data: vec![1, 2],
+                    next_cursor: Some("c2".into()),
+                },
+                Page {
+                    data: vec![3],
+                    next_cursor: None,
+                },
+            ]
+        );
+        let queries: Vec<_> = transport
+            .requests()
+            .iter()
+            .map(|req| req.url.query().unwrap().to_string())
+            .collect();
+        assert_eq!(queries, ["limit=2&cursor=c1", "limit=2&cursor=c2"]);
+    }
+
+    #[tokio::test]
+    async fn prefetching_is_bounded_and_ordered() {
+        let transport = MockTransport::default();
+        for i in 0..5 {
+            let next_cursor = (i < 4).then(|| format!("c{i}"));
+            transport.respond(
+                200,
+                json!({ "data": [i], "pagination_metadata": { "next_cursor": next_cursor } }),
+            );
+        }
+        let client = transport.client();
+        let params = ListParams::DEFAULT.prefetch(1);
+        let req = client.build_request(Method::GET, ["customers"]);
+        let mut items = pin!(client.stream_paginated_request::<u64>(&params, req));
+        assert_eq!(items.next().await.unwrap().unwrap(), 0);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // The first page has been consumed and one page is buffered.
+        assert_eq!(transport.requests().len(), 2);
+        let rest: Vec<u64> = items.try_collect().await.unwrap();
+        assert_eq!(rest, [1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn streams_outlive_the_client_and_params() {
+        let transport = MockTransport::default();
+        transport.respond(
+            200,
+            json!({ "data": [], "pagination_metadata": { "next_cursor": null } }),
+        );
+        let client = transport.client();
+        let params = crate::SubscriptionListParams::DEFAULT.customer_id(CustomerId::Orb("cus_1"));
+        let subscriptions = client.list_subscriptions(&params);
+        drop(client);
+        let subscriptions: Vec<_> = tokio::spawn(subscriptions.try_collect())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(subscriptions.is_empty());
+        let query = transport.requests()[0].url.query().unwrap().to_string();
+        assert_eq!(query, "customer_id=cus_1&limit=20");
+    }
+
+    #[test]
+    fn requests_track_their_path_template() {
+        let client = MockTransport::default().client();
+        let req = client.build_request(Method::GET, ["customers"].chain_id("cus_1"));
+        assert_eq!(req.template, "customers/{id}");
+        let req = req.inner.build().unwrap();
+        assert_eq!(req.url().path(), "/v1/customers/cus_1");
+    }
+
+    #[tokio::test]
+    async fn undecodable_responses_report_their_location() {
+        let transport = MockTransport::default();
+        transport.respond(200, json!({ "id": "cus_1", "name": 42 }));
+        let client = transport.client();
+        let err = client.get_customer("cus_1").await.unwrap_err();
+        match err {
+            Error::Decode(e) => {
+                assert_eq!(e.status_code, StatusCode::OK);
+                assert_eq!(e.path, "/v1/customers/cus_1");
+                assert_eq!(e.json_path, "name");
+                assert!(e.body.contains("cus_1"));
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+
+        transport.respond_with(HttpResponse {
+            status: StatusCode::BAD_GATEWAY,
+            headers: HeaderMap::new(),
+            body: b"<html>bad gateway</html>".to_vec(),
+        });
+        let req = client.build_request(Method::POST, ["customers"]);
+        let err = client
+            .send_request::<serde_json::Value>(req)
+            .await
+            .unwrap_err();
+        assert!(err.is_retryable());
+        match err {
+            Error::Decode(e) => {
+                assert_eq!(e.status_code, StatusCode::BAD_GATEWAY);
+                assert_eq!(e.body, "<html>bad gateway</html>");
+            }
+            e => panic!("unexpected error: {e}"),
         }
     }
 }
@@ -4,10 +4,10 @@ use serde::{Deserialize, Serialize};
 use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
 use time::OffsetDateTime;
 
-use crate::client::Client;
 use crate::client::ListParams;
+use crate::client::{Client, Page, RequestBuilder};
 use crate::error::Error;
-use crate::util::StrIteratorExt;
+use crate::util::PathExt;
 
 const ALERTS_PATH: [&str; 1] = ["alerts"];
 
@@ -62,7 +62,26 @@ impl<'a> AlertListParams<'a> {
     ///
     /// See [`ListParams::page_size`].
     pub const fn page_size(mut self, page_size: u64) -> Self {
-        self.inner = self.inner.page_size(page_size);
+        self.inner.page_size = page_size;
+        self
+    }
+
+    /// Sets the number of pages to fetch ahead of the consumer.
+    ///
+    /// See [`ListParams::prefetch`].
+    pub const fn prefetch(mut self, prefetch: usize) -> Self {
+        self.inner.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the cursor from which to start the list operation.
+    ///
+    /// See [`ListParams::cursor`].
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.inner = self.inner.cursor(cursor);
         self
     }
 
@@ -142,7 +161,7 @@ impl Client {
             Method::POST,
             ALERTS_PATH
             .chain_one("subscription_id")
-            .chain_one(subscription_id)
+            .chain_id(subscription_id)
             );
         let req = req.json(params);
         let res = self.send_request(req).await?;
@@ -154,20 +173,39 @@ impl Client {
         let req = self.build_request(
             Method::GET,
             ALERTS_PATH
-            .chain_one(alert_id)
+            .chain_id(alert_id)
             );
         let res = self.send_request(req).await?;
         Ok(res)
     }
 
     /// This endpoint returns a list of alerts within Orb.
-    pub fn list_alerts(&self, params: &AlertListParams) -> impl Stream<Item = Result<Alert, Error>> + '_ {
+    pub fn list_alerts(
+        &self,
+        params: &AlertListParams,
+    ) -> impl Stream<Item = Result<Alert, Error>> + 'static {
+        let req = self.list_alerts_request(params);
+        self.stream_paginated_request(&params.inner, req)
+    }
+
+    /// Lists alerts, one page at a time.
+    ///
+    /// Each page reports the cursor for the next page, which can be passed to
+    /// [`AlertListParams::cursor`] to resume the listing from that page.
+    pub fn list_alerts_pages(
+        &self,
+        params: &AlertListParams,
+    ) -> impl Stream<Item = Result<Page<Alert>, Error>> + 'static {
+        let req = self.list_alerts_request(params);
+        self.stream_paginated_pages(&params.inner, req)
+    }
+
+    fn list_alerts_request(&self, params: &AlertListParams) -> RequestBuilder {
         let req = self.build_request(Method::GET, ALERTS_PATH);
-        let req = match params.subscription_id_filter {
+        match params.subscription_id_filter {
             None => req,
             Some(subscription_id) => req.query(&[("subscription_id", subscription_id)]),
-        };
-        self.stream_paginated_request(&params.inner, req)
+        }
     }
 
     /// This endpoint is used to disable an alert.
@@ -175,7 +213,7 @@ impl Client {
         let req = self.build_request(
             Method::POST,
             ALERTS_PATH
-            .chain_one(alert_id)
+            .chain_id(alert_id)
             .chain_one("disable")
             );
         let res = self.send_request(req).await?;
@@ -187,7 +225,7 @@ impl Client {
         let req = self.build_request(
             Method::POST,
             ALERTS_PATH
-            .chain_one(alert_id)
+            .chain_id(alert_id)
             .chain_one("enable")
             );
         let res = self.send_request(req).await?;
@@ -199,7 +237,7 @@ impl Client {
         let req = self.build_request(
             Method::PUT,
             ALERTS_PATH
-            .chain_one(alert_id)
+            .chain_id(alert_id)
             );
         let req = req.json(params);
         let res = self.send_request(req).await?;
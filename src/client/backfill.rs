@@ -1,10 +1,20 @@
+use std::fmt;
+use std::time::Duration;
+
 use futures_core::Stream;
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
-use time::OffsetDateTime;
-use crate::{Client, Error, ListParams};
-use crate::util::StrIteratorExt;
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+use tokio::time::{self as tokio_time, Instant};
+use crate::{Client, Error, ListParams, Page};
+use crate::client::RequestBuilder;
+use crate::client::customers::OwnedCustomerId;
+use crate::client::events::{IngestEventRequest, IngestEventResponse, IngestionMode};
+use crate::config::RetryPolicy;
+use crate::ingest::IngesterConfig;
+use crate::util::PathExt;
 
 const BACKFILLS: [&str; 2] = ["events", "backfills"];
 const CLOSE: &str = "close";
@@ -24,10 +34,13 @@ pub struct CreateBackfillParams {
     /// The time at which no more events will be accepted for this backfill. The backfill will automatically begin reflecting throughout Orb at the close time. If not specified, it will default to 1 day after the creation of the backfill.
     #[serde(default, with = "time::serde::rfc3339::option")]
     pub close_time: Option<OffsetDateTime>,
-    /// The ID of the customer to which this backfill is scoped. (cannot be used with external_customer_id)
-    pub customer_id: Option<String>,
-    /// The external customer ID of the customer to which this backfill is scoped. (cannot be used with customer_id)
-    pub external_customer_id: Option<String>,
+    /// The customer to which this backfill is scoped. If not specified, the backfill is not scoped to a single customer.
+    #[serde(flatten)]
+    pub customer_id: Option<OwnedCustomerId>,
+    /// A boolean computed property that filters the set of events to deprecate.
+    /// If specified, matching events in the timeframe are deprecated rather than replaced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecation_filter: Option<String>,
 }
 
 /// The status of the backfill. See https://docs.withorb.com/reference/fetch-backfill
@@ -57,17 +70,25 @@ pub struct BackfillStatusResponse {
     /// The time at which this backfill was reverted.
     #[serde(default, with = "time::serde::rfc3339::option")]
     pub reverted_at: Option<OffsetDateTime>,
-    /// Undocumented
+    /// The (inclusive) start of the usage timeframe affected by this backfill.
     #[serde(with = "time::serde::rfc3339")]
     pub timeframe_start: OffsetDateTime,
-    /// Undocumented
+    /// The (exclusive) end of the usage timeframe affected by this backfill.
     #[serde(with = "time::serde::rfc3339")]
     pub timeframe_end: OffsetDateTime,
-    /// Undocumented
+    /// The time at which this backfill was created.
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
     /// The customer ID this backfill is scoped to. If null, this backfill is not scoped to a single customer.
-    pub customer_id: Option<String>
+    pub customer_id: Option<String>,
+    /// The number of events ingested in this backfill.
+    #[serde(default)]
+    pub events_ingested: u64,
+    /// If true, this backfill replaces all existing events in its timeframe.
+    #[serde(default)]
+    pub replace_existing_events: bool,
+    /// The boolean computed property that filters the set of events deprecated by this backfill.
+    pub deprecation_filter: Option<String>,
 }
 
 /// A list of all backfills. See https://docs.withorb.com/reference/list-backfills.
@@ -77,6 +98,70 @@ pub struct ListBackfillsResponse {
     pub data: Vec<BackfillStatusResponse>
 }
 
+/// Parameters for a backfill list operation.
+#[derive(Debug, Clone)]
+pub struct BackfillListParams {
+    inner: ListParams,
+    created_at_start: Option<OffsetDateTime>,
+    created_at_end: Option<OffsetDateTime>,
+}
+
+impl Default for BackfillListParams {
+    fn default() -> BackfillListParams {
+        BackfillListParams::DEFAULT
+    }
+}
+
+impl BackfillListParams {
+    /// The default backfill list parameters.
+    ///
+    /// Exposed as a constant for use in constant evaluation contexts.
+    pub const DEFAULT: BackfillListParams = BackfillListParams {
+        inner: ListParams::DEFAULT,
+        created_at_start: None,
+        created_at_end: None,
+    };
+
+    /// Sets the page size for the list operation.
+    ///
+    /// See [`ListParams::page_size`].
+    pub const fn page_size(mut self, page_size: u64) -> Self {
+        self.inner.page_size = page_size;
+        self
+    }
+
+    /// Sets the number of pages to fetch ahead of the consumer.
+    ///
+    /// See [`ListParams::prefetch`].
+    pub const fn prefetch(mut self, prefetch: usize) -> Self {
+        self.inner.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the cursor from which to start the list operation.
+    ///
+    /// See [`ListParams::cursor`].
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.inner = self.inner.cursor(cursor);
+        self
+    }
+
+    /// Filters the listing to backfills created on or after the specified datetime.
+    pub const fn created_at_start(mut self, start: OffsetDateTime) -> Self {
+        self.created_at_start = Some(start);
+        self
+    }
+
+    /// Filters the listing to backfills created before the specified datetime.
+    pub const fn created_at_end(mut self, end: OffsetDateTime) -> Self {
+        self.created_at_end = Some(end);
+        self
+    }
+}
+
 impl Client {
     /// See https://docs.withorb.com/reference/create-backfill
     pub async fn create_backfill(&self, params: &CreateBackfillParams) -> Result<BackfillStatusResponse, Error> {
@@ -87,25 +172,482 @@ impl Client {
 
     /// See https://docs.withorb.com/reference/close-backfill
     pub async fn close_backfill(&self, backfill_id: String) -> Result<BackfillStatusResponse, Error> {
-        let req = self.build_request(Method::POST, BACKFILLS.chain_one(backfill_id).chain_one(CLOSE));
+        let req = self.build_request(Method::POST, BACKFILLS.chain_id(backfill_id).chain_one(CLOSE));
         self.send_request(req).await
     }
 
     /// See https://docs.withorb.com/reference/revert-backfill
     pub async fn revert_backfill(&self, backfill_id: String) -> Result<BackfillStatusResponse, Error> {
-        let req = self.build_request(Method::POST, BACKFILLS.chain_one(backfill_id).chain_one(REVERT));
+        let req = self.build_request(Method::POST, BACKFILLS.chain_id(backfill_id).chain_one(REVERT));
         self.send_request(req).await
     }
 
+    /// Lists backfills as configured by `params`.
+    ///
     /// See https://docs.withorb.com/reference/list-backfills
-    pub fn list_backfills(&self) -> impl Stream<Item = Result<BackfillStatusResponse, Error>> + '_ {
-        let req = self.build_request(Method::GET, BACKFILLS);
-        self.stream_paginated_request(&ListParams::default(), req)
+    pub fn list_backfills(
+        &self,
+        params: &BackfillListParams,
+    ) -> impl Stream<Item = Result<BackfillStatusResponse, Error>> + 'static {
+        let req = self.list_backfills_request(params);
+        self.stream_paginated_request(&params.inner, req)
+    }
+
+    /// Lists backfills as configured by `params`, one page at a time.
+    ///
+    /// Each page reports the cursor for the next page, which can be passed to
+    /// [`BackfillListParams::cursor`] to resume the listing from that page.
+    pub fn list_backfills_pages(
+        &self,
+        params: &BackfillListParams,
+    ) -> impl Stream<Item = Result<Page<BackfillStatusResponse>, Error>> + 'static {
+        let req = self.list_backfills_request(params);
+        self.stream_paginated_pages(&params.inner, req)
+    }
+
+    fn list_backfills_request(&self, params: &BackfillListParams) -> RequestBuilder {
+        let mut req = self.build_request(Method::GET, BACKFILLS);
+        for (name, filter) in [
+            ("created_at[gte]", params.created_at_start),
+            ("created_at[lt]", params.created_at_end),
+        ] {
+            if let Some(datetime) = filter {
+                // Orb requires supplied datetimes be in UTC
+                let datetime = datetime.to_offset(UtcOffset::UTC).format(&Rfc3339).unwrap();
+                req = req.query(&[(name, datetime)]);
+            }
+        }
+        req
     }
 
     /// See https://docs.withorb.com/reference/fetch-backfill
     pub async fn fetch_backfill(&self, backfill_id: String) -> Result<BackfillStatusResponse, Error> {
-        let req = self.build_request(Method::GET, BACKFILLS.chain_one(backfill_id));
+        let req = self.build_request(Method::GET, BACKFILLS.chain_id(backfill_id));
         self.send_request(req).await
     }
-}
\ No newline at end of file
+}
+
+/// A handle to a backfill that drives it through its lifecycle.
+///
+/// Backfilling events is a multi-step protocol: create the backfill, ingest
+/// events into it, close it, and wait for Orb to reflect it in usage data.
+/// Create a backfill with [`Backfill::create`], ingest events into it with
+/// [`Backfill::ingest`], and then close it and wait for it to be reflected
+/// with [`Backfill::finish`].
+///
+/// Batches that fail with a retryable error are retried according to the
+/// handle's [`RetryPolicy`]. If ingesting events still fails, the backfill is
+/// reverted, so that a partially ingested backfill is never reflected.
+#[derive(Debug, Clone)]
+pub struct Backfill {
+    client: Client,
+    status: BackfillStatusResponse,
+    poll_interval: Duration,
+    retry_policy: RetryPolicy,
+}
+
+impl Backfill {
+    /// The default interval at which [`Backfill::wait_reflected`] polls the
+    /// status of the backfill.
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Creates a backfill.
+    pub async fn create(client: &Client, params: &CreateBackfillParams) -> Result<Backfill, Error> {
+        let status = client.create_backfill(params).await?;
+        Ok(Backfill {
+            client: client.clone(),
+            status,
+            poll_interval: Backfill::DEFAULT_POLL_INTERVAL,
+            retry_policy: RetryPolicy::DEFAULT,
+        })
+    }
+
+    /// Sets the interval at which [`Backfill::wait_reflected`] polls the
+    /// status of the backfill.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Sets the policy for retrying batches that [`Backfill::ingest`] fails
+    /// to send.
+    ///
+    /// Defaults to [`RetryPolicy::DEFAULT`].
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the ID of the backfill.
+    pub fn id(&self) -> &str {
+        &self.status.id
+    }
+
+    /// Returns the most recently observed state of the backfill.
+    pub fn status(&self) -> &BackfillStatusResponse {
+        &self.status
+    }
+
+    /// Ingests events into the backfill.
+    ///
+    /// Events are sent in batches of at most
+    /// [`IngesterConfig::MAX_BATCH_SIZE`]. Events that fail validation are
+    /// reported in the response and do not abort the backfill. A batch that
+    /// fails with a retryable error is retried according to the handle's
+    /// retry policy. If a batch fails with an error that is not retryable, or
+    /// retries are exhausted, the backfill is reverted, and the error is
+    /// returned as a [`BackfillError::Ingest`].
+    pub async fn ingest(
+        &mut self,
+        events: &[IngestEventRequest<'_>],
+    ) -> Result<IngestEventResponse, BackfillError> {
+        let mut res = IngestEventResponse {
+            validation_failed: vec![],
+            locally_rejected: vec![],
+            debug: None,
+        };
+        for batch in events.chunks(IngesterConfig::MAX_BATCH_SIZE) {
+            let policy = &self.retry_policy;
+            let mut attempt = 0;
+            let error = loop {
+                attempt += 1;
+                let backfill_id = Some(self.status.id.clone());
+                let mode = IngestionMode::Production;
+                match self.client.ingest_events(mode, backfill_id, batch).await {
+                    Ok(batch_res) => {
+                        res.validation_failed.extend(batch_res.validation_failed);
+                        res.locally_rejected.extend(batch_res.locally_rejected);
+                        break None;
+                    }
+                    Err(e) if e.is_retryable() && attempt < policy.max_attempts => {
+                        match policy.delay_for(attempt, e.retry_after()) {
+                            Some(delay) => tokio_time::sleep(delay).await,
+                            None => break Some(e),
+                        }
+                    }
+                    Err(e) => break Some(e),
+                }
+            };
+            if let Some(error) = error {
+                let revert_error = self.revert().await.err();
+                return Err(BackfillError::Ingest {
+                    error,
+                    revert_error,
+                });
+            }
+        }
+        Ok(res)
+    }
+
+    /// Closes the backfill, so that it begins to be reflected in usage data.
+    pub async fn close(&mut self) -> Result<&BackfillStatusResponse, Error> {
+        self.status = self.client.close_backfill(self.status.id.clone()).await?;
+        Ok(&self.status)
+    }
+
+    /// Reverts the backfill.
+    pub async fn revert(&mut self) -> Result<&BackfillStatusResponse, Error> {
+        self.status = self.client.revert_backfill(self.status.id.clone()).await?;
+        Ok(&self.status)
+    }
+
+    /// Waits for the backfill to be reflected in usage data.
+    ///
+    /// Polls the status of the backfill until it is reflected. Returns
+    /// [`BackfillError::Timeout`] if the backfill is not reflected within
+    /// `timeout`, or [`BackfillError::Reverted`] if the backfill is reverted
+    /// in the meantime.
+    pub async fn wait_reflected(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<&BackfillStatusResponse, BackfillError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.status.status {
+                BackfillStatus::Reflected => return Ok(&self.status),
+                BackfillStatus::PendingRevert | BackfillStatus::Reverted => {
+                    return Err(BackfillError::Reverted(self.status.clone()))
+                }
+                BackfillStatus::Pending => (),
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(BackfillError::Timeout(self.status.clone()));
+            }
+            tokio_time::sleep(self.poll_interval.min(deadline - now)).await;
+            self.status = self
+                .client
+                .fetch_backfill(self.status.id.clone())
+                .await
+                .map_err(BackfillError::Request)?;
+        }
+    }
+
+    /// Closes the backfill and waits for it to be reflected in usage data.
+    ///
+    /// See [`Backfill::close`] and [`Backfill::wait_reflected`].
+    pub async fn finish(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<&BackfillStatusResponse, BackfillError> {
+        self.close().await.map_err(BackfillError::Request)?;
+        self.wait_reflected(timeout).await
+    }
+}
+
+/// An error that occurred while driving a [`Backfill`].
+#[derive(Debug)]
+pub enum BackfillError {
+    /// Events could not be ingested into the backfill, so the backfill was
+    /// reverted.
+    Ingest {
+        /// The error ingesting events.
+        error: Error,
+        /// The error reverting the backfill, if it could not be reverted.
+        revert_error: Option<Error>,
+    },
+    /// The backfill was reverted before it was reflected.
+    Reverted(BackfillStatusResponse),
+    /// The backfill was not reflected before the timeout elapsed.
+    Timeout(BackfillStatusResponse),
+    /// A request to manage the backfill failed.
+    Request(Error),
+}
+
+impl fmt::Display for BackfillError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BackfillError::Ingest { error, revert_error: None } => {
+                write!(f, "backfill reverted after failing to ingest events: {error}")
+            }
+            BackfillError::Ingest { error, revert_error: Some(revert_error) } => write!(
+                f,
+                "failed to ingest events into backfill: {error}; failed to revert backfill: {revert_error}"
+            ),
+            BackfillError::Reverted(status) => write!(f, "backfill {} was reverted", status.id),
+            BackfillError::Timeout(status) => {
+                write!(f, "timed out waiting for backfill {} to be reflected", status.id)
+            }
+            BackfillError::Request(error) => write!(f, "backfill request failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for BackfillError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BackfillError::Ingest { error, .. } | BackfillError::Request(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+    use serde_json::json;
+
+    use super::*;
+    use crate::transport::mock::{test_event, MockTransport};
+
+    fn status(status: &str) -> serde_json::Value {
+        json!({
+            "id": "bf",
+            "status": status,
+            "close_time": "1970-01-02T00:00:00Z",
+            "reverted_at": null,
+            "timeframe_start": "1970-01-01T00:00:00Z",
+            "timeframe_end": "1970-01-02T00:00:00Z",
+            "created_at": "1970-01-01T00:00:00Z",
+            "customer_id": null,
+            "events_ingested": 0,
+            "replace_existing_events": true,
+            "deprecation_filter": null,
+        })
+    }
+
+    fn params() -> CreateBackfillParams {
+        CreateBackfillParams {
+            replace_existing_events: true,
+            timeframe_start: OffsetDateTime::UNIX_EPOCH,
+            timeframe_end: OffsetDateTime::UNIX_EPOCH + Duration::from_secs(24 * 60 * 60),
+            close_time: None,
+            customer_id: None,
+            deprecation_filter: None,
+        }
+    }
+
+    fn paths(transport: &MockTransport) -> Vec<String> {
+        transport
+            .requests()
+            .iter()
+            .map(|req| req.url.path().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn backfill_is_ingested_closed_and_reflected() {
+        let transport = MockTransport::default();
+        transport.respond(200, status("pending"));
+        transport.respond(200, json!({}));
+        transport.respond(200, status("pending"));
+        transport.respond(200, status("pending"));
+        transport.respond(200, status("reflected"));
+        let client = transport.client();
+
+        let mut backfill = Backfill::create(&client, &params())
+            .await
+            .unwrap()
+            .poll_interval(Duration::from_millis(1));
+        let event = test_event("a");
+        backfill.ingest(&[event.as_request()]).await.unwrap();
+        let status = backfill.finish(Duration::from_secs(10)).await.unwrap();
+        assert_eq!(status.status, BackfillStatus::Reflected);
+        assert_eq!(
+            paths(&transport),
+            [
+                "/v1/events/backfills",
+                "/v1/ingest",
+                "/v1/events/backfills/bf/close",
+                "/v1/events/backfills/bf",
+                "/v1/events/backfills/bf",
+            ]
+        );
+        assert_eq!(
+            transport.requests()[1].url.query(),
+            Some("debug=false&backfill_id=bf")
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_is_reverted_when_ingestion_fails() {
+        let transport = MockTransport::default();
+        transport.respond(200, status("pending"));
+        transport.respond(400, json!({ "title": "bad request" }));
+        transport.respond(200, status("pending_revert"));
+        let client = transport.client();
+
+        let mut backfill = Backfill::create(&client, &params()).await.unwrap();
+        let event = test_event("a");
+        let err = backfill.ingest(&[event.as_request()]).await.unwrap_err();
+        let BackfillError::Ingest { revert_error, .. } = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert!(revert_error.is_none());
+        assert_eq!(backfill.status().status, BackfillStatus::PendingRevert);
+        assert_eq!(
+            paths(&transport).last().unwrap(),
+            "/v1/events/backfills/bf/revert"
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_retries_transient_ingestion_failures() {
+        let transport = MockTransport::default();
+        transport.respond(200, status("pending"));
+        transport.respond(503, json!({ "title": "unavailable" }));
+        transport.respond(200, json!({}));
+        let client = transport.client();
+
+        let mut backfill = Backfill::create(&client, &params()).await.unwrap();
+        let event = test_event("a");
+        backfill.ingest(&[event.as_request()]).await.unwrap();
+        assert_eq!(backfill.status().status, BackfillStatus::Pending);
+        assert_eq!(
+            paths(&transport),
+            ["/v1/events/backfills", "/v1/ingest", "/v1/ingest"]
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_wait_times_out() {
+        let transport = MockTransport::default();
+        transport.respond(200, status("pending"));
+        transport.respond(200, status("pending"));
+        let client = transport.client();
+
+        let mut backfill = Backfill::create(&client, &params())
+            .await
+            .unwrap()
+            .poll_interval(Duration::from_secs(3600));
+        let err = backfill
+            .wait_reflected(Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BackfillError::Timeout(_)), "{err}");
+    }
+
+    #[test]
+    fn create_backfill_params_target_customer() {
+        let params = CreateBackfillParams {
+            customer_id: Some(OwnedCustomerId::External("ext".into())),
+            deprecation_filter: Some("region = 'us'".into()),
+            ..params()
+        };
+        let value = serde_json::to_value(&params).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "replace_existing_events": true,
+                "timeframe_start": "1970-01-01T00:00:00Z",
+                "timeframe_end": "1970-01-02T00:00:00Z",
+                "close_time": null,
+                "external_customer_id": "ext",
+                "deprecation_filter": "region = 'us'",
+            })
+        );
+        let params: CreateBackfillParams = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            params.customer_id,
+            Some(OwnedCustomerId::External("ext".into()))
+        );
+
+        let params = CreateBackfillParams {
+            deprecation_filter: None,
+            ..params
+        };
+        let value = serde_json::to_value(&params).unwrap();
+        assert!(value.get("deprecation_filter").is_none());
+    }
+
+    #[test]
+    fn backfill_status_response_defaults_missing_fields() {
+        let mut value = status("pending");
+        let fields = value.as_object_mut().unwrap();
+        fields.remove("events_ingested");
+        fields.remove("replace_existing_events");
+        fields.remove("deprecation_filter");
+        let status: BackfillStatusResponse = serde_json::from_value(value).unwrap();
+        assert_eq!(status.events_ingested, 0);
+        assert!(!status.replace_existing_events);
+        assert_eq!(status.deprecation_filter, None);
+    }
+
+    #[tokio::test]
+    async fn list_backfills_filters_by_creation_time() {
+        let transport = MockTransport::default();
+        transport.respond(
+            200,
+            json!({
+                "data": [status("reflected")],
+                "pagination_metadata": { "next_cursor": null },
+            }),
+        );
+        let client = transport.client();
+
+        let params = BackfillListParams::DEFAULT
+            .page_size(10)
+            .created_at_start(OffsetDateTime::UNIX_EPOCH)
+            .created_at_end(OffsetDateTime::UNIX_EPOCH + Duration::from_secs(60));
+        let backfills: Vec<_> = client.list_backfills(&params).try_collect().await.unwrap();
+        assert_eq!(backfills.len(), 1);
+        assert!(backfills[0].replace_existing_events);
+        assert_eq!(
+            transport.requests()[0].url.query(),
+            Some(
+                "created_at%5Bgte%5D=1970-01-01T00%3A00%3A00Z\
+                 &created_at%5Blt%5D=1970-01-01T00%3A01%3A00Z&limit=10"
+            )
+        );
+    }
+}
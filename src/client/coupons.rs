@@ -3,7 +3,7 @@ use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
-use crate::client::Client;
+use crate::client::{Client, Page, RequestBuilder};
 use crate::config::ListParams;
 use crate::error::Error;
 
@@ -95,7 +95,26 @@ impl<'a> CouponListParams<'a> {
     ///
     /// See [`ListParams::page_size`].
     pub const fn page_size(mut self, page_size: u64) -> Self {
-        self.inner = self.inner.page_size(page_size);
+        self.inner.page_size = page_size;
+        self
+    }
+
+    /// Sets the number of pages to fetch ahead of the consumer.
+    ///
+    /// See [`ListParams::prefetch`].
+    pub const fn prefetch(mut self, prefetch: usize) -> Self {
+        self.inner.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the cursor from which to start the list operation.
+    ///
+    /// See [`ListParams::cursor`].
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.inner = self.inner.cursor(cursor);
         self
     }
 
@@ -120,17 +139,33 @@ impl Client {
     pub fn list_coupons(
         &self,
         params: &CouponListParams,
-    ) -> impl Stream<Item = Result<Coupon, Error>> + '_ {
+    ) -> impl Stream<Item = Result<Coupon, Error>> + 'static {
+        let req = self.list_coupons_request(params);
+        self.stream_paginated_request(&params.inner, req)
+    }
+
+    /// Lists all coupons, one page at a time.
+    ///
+    /// Each page reports the cursor for the next page, which can be passed to
+    /// [`CouponListParams::cursor`] to resume the listing from that page.
+    pub fn list_coupons_pages(
+        &self,
+        params: &CouponListParams,
+    ) -> impl Stream<Item = Result<Page<Coupon>, Error>> + 'static {
+        let req = self.list_coupons_request(params);
+        self.stream_paginated_pages(&params.inner, req)
+    }
+
+    fn list_coupons_request(&self, params: &CouponListParams) -> RequestBuilder {
         let req = self.build_request(Method::GET, COUPONS_PATH);
         let req = match params.redemption_code_filter {
             Some(redemption_code) => req.query(&[("redemption_code", redemption_code)]),
             None => req,
         };
-        let req = match params.show_archived_filter {
+        match params.show_archived_filter {
             Some(show_archived) => req.query(&[("show_archived", show_archived)]),
             None => req,
-        };
-        self.stream_paginated_request(&params.inner, req)
+        }
     }
 }
 
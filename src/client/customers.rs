@@ -15,18 +15,18 @@
 
 use futures_core::Stream;
 use futures_util::stream::TryStreamExt;
-use reqwest::{Method, RequestBuilder};
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
 use time::format_description::well_known::Rfc3339;
 use time::{OffsetDateTime, UtcOffset};
 
-use crate::client::taxes::{TaxId, TaxIdRequest};
-use crate::client::Client;
+use crate::client::taxes::{OwnedTaxIdRequest, TaxId, TaxIdRequest};
+use crate::client::{Client, Page, RequestBuilder};
 use crate::config::ListParams;
 use crate::error::Error;
 use crate::serde::Empty;
-use crate::util::StrIteratorExt;
+use crate::util::{require_field, require_some, PathExt};
 
 const CUSTOMERS_PATH: [&str; 1] = ["customers"];
 
@@ -52,6 +52,48 @@ impl<'a> Default for CustomerId<'a> {
     }
 }
 
+/// An owned [`CustomerId`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum OwnedCustomerId {
+    /// An Orb customer ID.
+    #[serde(rename = "customer_id")]
+    Orb(String),
+    /// A external customer ID.
+    #[serde(rename = "external_customer_id")]
+    External(String),
+}
+
+impl OwnedCustomerId {
+    /// Borrows this ID as a [`CustomerId`].
+    pub fn as_id(&self) -> CustomerId<'_> {
+        match self {
+            OwnedCustomerId::Orb(id) => CustomerId::Orb(id),
+            OwnedCustomerId::External(id) => CustomerId::External(id),
+        }
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        match self {
+            OwnedCustomerId::Orb(id) | OwnedCustomerId::External(id) => id,
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedCustomerId> for CustomerId<'a> {
+    fn from(id: &'a OwnedCustomerId) -> CustomerId<'a> {
+        id.as_id()
+    }
+}
+
+impl From<CustomerId<'_>> for OwnedCustomerId {
+    fn from(id: CustomerId<'_>) -> OwnedCustomerId {
+        match id {
+            CustomerId::Orb(id) => OwnedCustomerId::Orb(id.into()),
+            CustomerId::External(id) => OwnedCustomerId::External(id.into()),
+        }
+    }
+}
+
 /// The subset of [`Customer`] used in create requests.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct CreateCustomerRequest<'a> {
@@ -145,6 +187,207 @@ pub struct CustomerPaymentProviderRequest<'a> {
     pub id: &'a str,
 }
 
+/// An owned [`CreateCustomerRequest`].
+///
+/// Unlike [`CreateCustomerRequest`], an owned request does not borrow its
+/// fields, so it can be built in one place and sent later, e.g., from another
+/// task. Construct one with [`OwnedCreateCustomerRequest::builder`], and pass
+/// it to [`Client::create_customer`] via [`as_request`].
+///
+/// [`as_request`]: OwnedCreateCustomerRequest::as_request
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedCreateCustomerRequest {
+    /// An optional user-defined ID for this customer resource, used throughout
+    /// the system as an alias for this customer.
+    pub external_id: Option<String>,
+    /// The full name of the customer.
+    pub name: String,
+    /// A valid email for the customer, to be used for notifications.
+    pub email: String,
+    /// Additional email addresses for this customer.
+    pub additional_emails: Option<Vec<String>>,
+    /// The customer's timezone as an identifier from the IANA timezone
+    /// database.
+    pub timezone: Option<String>,
+    /// The external payments or invoicing solution connected to the customer.
+    pub payment_provider: Option<OwnedCustomerPaymentProviderRequest>,
+    /// The customer's shipping address.
+    pub shipping_address: Option<OwnedAddressRequest>,
+    /// The customer's billing address.
+    pub billing_address: Option<OwnedAddressRequest>,
+    /// The currency used for the customer's invoices and balance.
+    pub currency: Option<String>,
+    /// The tax ID details to display on the customer's invoice.
+    pub tax_id: Option<OwnedTaxIdRequest>,
+    /// An idempotency key can ensure that if the same request comes in
+    /// multiple times in a 48-hour period, only one makes changes.
+    pub idempotency_key: Option<String>,
+    /// Whether to send emails
+    pub email_delivery: Option<bool>,
+    /// Tax configuration for the customer.
+    pub tax_configuration: Option<TaxConfiguration>,
+}
+
+impl OwnedCreateCustomerRequest {
+    /// Returns a builder for a create customer request.
+    pub fn builder() -> CreateCustomerRequestBuilder {
+        CreateCustomerRequestBuilder::default()
+    }
+
+    /// Borrows this request as a [`CreateCustomerRequest`].
+    pub fn as_request(&self) -> CreateCustomerRequest<'_> {
+        CreateCustomerRequest {
+            external_id: self.external_id.as_deref(),
+            name: &self.name,
+            email: &self.email,
+            additional_emails: self
+                .additional_emails
+                .as_ref()
+                .map(|emails| emails.iter().map(String::as_str).collect()),
+            timezone: self.timezone.as_deref(),
+            payment_provider: self.payment_provider.as_ref().map(Into::into),
+            shipping_address: self.shipping_address.as_ref().map(Into::into),
+            billing_address: self.billing_address.as_ref().map(Into::into),
+            currency: self.currency.clone(),
+            tax_id: self.tax_id.as_ref().map(Into::into),
+            idempotency_key: self.idempotency_key.as_deref(),
+            email_delivery: self.email_delivery,
+            tax_configuration: self.tax_configuration.clone(),
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedCreateCustomerRequest> for CreateCustomerRequest<'a> {
+    fn from(request: &'a OwnedCreateCustomerRequest) -> CreateCustomerRequest<'a> {
+        request.as_request()
+    }
+}
+
+/// A builder for an [`OwnedCreateCustomerRequest`].
+///
+/// The `name` and `email` fields are required.
+#[derive(Debug, Default, Clone)]
+pub struct CreateCustomerRequestBuilder {
+    request: OwnedCreateCustomerRequest,
+}
+
+impl CreateCustomerRequestBuilder {
+    /// Sets the customer's external ID.
+    pub fn external_id<S: Into<String>>(mut self, external_id: S) -> Self {
+        self.request.external_id = Some(external_id.into());
+        self
+    }
+
+    /// Sets the customer's full name.
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.request.name = name.into();
+        self
+    }
+
+    /// Sets the customer's email.
+    pub fn email<S: Into<String>>(mut self, email: S) -> Self {
+        self.request.email = email.into();
+        self
+    }
+
+    /// Adds an additional email address for the customer.
+    pub fn additional_email<S: Into<String>>(mut self, email: S) -> Self {
+        self.request
+            .additional_emails
+            .get_or_insert_with(Vec::new)
+            .push(email.into());
+        self
+    }
+
+    /// Sets the customer's timezone.
+    pub fn timezone<S: Into<String>>(mut self, timezone: S) -> Self {
+        self.request.timezone = Some(timezone.into());
+        self
+    }
+
+    /// Sets the external payments or invoicing solution connected to the
+    /// customer.
+    pub fn payment_provider<S: Into<String>>(mut self, kind: PaymentProvider, id: S) -> Self {
+        self.request.payment_provider = Some(OwnedCustomerPaymentProviderRequest {
+            kind,
+            id: id.into(),
+        });
+        self
+    }
+
+    /// Sets the customer's shipping address.
+    pub fn shipping_address(mut self, address: OwnedAddressRequest) -> Self {
+        self.request.shipping_address = Some(address);
+        self
+    }
+
+    /// Sets the customer's billing address.
+    pub fn billing_address(mut self, address: OwnedAddressRequest) -> Self {
+        self.request.billing_address = Some(address);
+        self
+    }
+
+    /// Sets the currency used for the customer's invoices and balance.
+    pub fn currency<S: Into<String>>(mut self, currency: S) -> Self {
+        self.request.currency = Some(currency.into());
+        self
+    }
+
+    /// Sets the tax ID details to display on the customer's invoice.
+    pub fn tax_id(mut self, tax_id: OwnedTaxIdRequest) -> Self {
+        self.request.tax_id = Some(tax_id);
+        self
+    }
+
+    /// Sets the idempotency key for the request.
+    pub fn idempotency_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.request.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Sets whether to send emails to the customer.
+    pub fn email_delivery(mut self, email_delivery: bool) -> Self {
+        self.request.email_delivery = Some(email_delivery);
+        self
+    }
+
+    /// Sets the tax configuration for the customer.
+    pub fn tax_configuration(mut self, tax_configuration: TaxConfiguration) -> Self {
+        self.request.tax_configuration = Some(tax_configuration);
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// Returns [`Error::InvalidRequest`] if a required field is missing.
+    pub fn build(self) -> Result<OwnedCreateCustomerRequest, Error> {
+        require_field(&self.request.name, "name")?;
+        require_field(&self.request.email, "email")?;
+        Ok(self.request)
+    }
+}
+
+/// An owned [`CustomerPaymentProviderRequest`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedCustomerPaymentProviderRequest {
+    /// The external payments or invoicing solution type.
+    pub kind: PaymentProvider,
+    /// The ID of this customer in an external payments solution, such as
+    /// Stripe.
+    pub id: String,
+}
+
+impl<'a> From<&'a OwnedCustomerPaymentProviderRequest> for CustomerPaymentProviderRequest<'a> {
+    fn from(
+        provider: &'a OwnedCustomerPaymentProviderRequest,
+    ) -> CustomerPaymentProviderRequest<'a> {
+        CustomerPaymentProviderRequest {
+            kind: provider.kind.clone(),
+            id: &provider.id,
+        }
+    }
+}
+
 // Deleted variants are immediately filtered out, so boxing the larger
 // `Normal` variant would result in an unnecessary heap allocation.
 #[allow(clippy::large_enum_variant)]
@@ -155,6 +398,25 @@ pub(crate) enum CustomerResponse {
     Deleted { id: String, deleted: bool },
 }
 
+impl CustomerResponse {
+    /// Returns the customer, or `None` if the customer has been deleted.
+    pub(crate) fn into_customer(self) -> Result<Option<Customer>, Error> {
+        match self {
+            CustomerResponse::Normal(c) => Ok(Some(c)),
+            CustomerResponse::Deleted {
+                id: _,
+                deleted: true,
+            } => Ok(None),
+            CustomerResponse::Deleted { id, deleted: false } => Err(Error::UnexpectedResponse {
+                detail: format!(
+                    "customer {id} used deleted response shape \
+                    but deleted field was `false`"
+                ),
+            }),
+        }
+    }
+}
+
 /// An Orb customer.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Customer {
@@ -269,6 +531,36 @@ pub struct Address {
     pub state: Option<String>,
 }
 
+/// An owned [`AddressRequest`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct OwnedAddressRequest {
+    /// The city.
+    pub city: Option<String>,
+    /// The country code.
+    pub country: Option<String>,
+    /// The first line of the street address.
+    pub line1: Option<String>,
+    /// The second line of the street address.
+    pub line2: Option<String>,
+    /// The postal code.
+    pub postal_code: Option<String>,
+    /// The state.
+    pub state: Option<String>,
+}
+
+impl<'a> From<&'a OwnedAddressRequest> for AddressRequest<'a> {
+    fn from(address: &'a OwnedAddressRequest) -> AddressRequest<'a> {
+        AddressRequest {
+            city: address.city.as_deref(),
+            country: address.country.as_deref(),
+            line1: address.line1.as_deref(),
+            line2: address.line2.as_deref(),
+            postal_code: address.postal_code.as_deref(),
+            state: address.state.as_deref(),
+        }
+    }
+}
+
 /// Tax configuration for a customer.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(tag = "tax_provider")]
@@ -363,6 +655,237 @@ pub struct AddVoidCreditLedgerEntryRequestParams<'a> {
     pub description: Option<&'a str>,
 }
 
+/// An owned [`LedgerEntryRequest`].
+///
+/// Construct one with [`OwnedLedgerEntryRequest::increment`] or
+/// [`OwnedLedgerEntryRequest::void`], and pass it to
+/// [`Client::create_ledger_entry`] via [`as_request`].
+///
+/// [`as_request`]: OwnedLedgerEntryRequest::as_request
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OwnedLedgerEntryRequest {
+    /// Increment a credit balance
+    Increment(OwnedAddIncrementCreditLedgerEntryRequestParams),
+    /// Void an existing ledger entry
+    Void(OwnedAddVoidCreditLedgerEntryRequestParams),
+}
+
+impl OwnedLedgerEntryRequest {
+    /// Returns a builder for an increment ledger entry request.
+    pub fn increment() -> IncrementLedgerEntryRequestBuilder {
+        IncrementLedgerEntryRequestBuilder::default()
+    }
+
+    /// Returns a builder for a void ledger entry request.
+    pub fn void() -> VoidLedgerEntryRequestBuilder {
+        VoidLedgerEntryRequestBuilder::default()
+    }
+
+    /// Borrows this request as a [`LedgerEntryRequest`].
+    pub fn as_request(&self) -> LedgerEntryRequest<'_> {
+        match self {
+            OwnedLedgerEntryRequest::Increment(params) => {
+                LedgerEntryRequest::Increment(AddIncrementCreditLedgerEntryRequestParams {
+                    amount: params.amount.clone(),
+                    description: params.description.as_deref(),
+                    expiry_date: params.expiry_date,
+                    effective_date: params.effective_date,
+                    per_unit_cost_basis: params.per_unit_cost_basis.as_deref(),
+                    invoice_settings: params.invoice_settings.as_ref().map(|settings| {
+                        CreditLedgerInvoiceSettingsRequestParams {
+                            auto_collection: settings.auto_collection,
+                            net_terms: settings.net_terms,
+                            memo: settings.memo.as_deref(),
+                        }
+                    }),
+                })
+            }
+            OwnedLedgerEntryRequest::Void(params) => {
+                LedgerEntryRequest::Void(AddVoidCreditLedgerEntryRequestParams {
+                    amount: params.amount.clone(),
+                    block_id: &params.block_id,
+                    void_reason: params.void_reason.clone(),
+                    description: params.description.as_deref(),
+                })
+            }
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedLedgerEntryRequest> for LedgerEntryRequest<'a> {
+    fn from(request: &'a OwnedLedgerEntryRequest) -> LedgerEntryRequest<'a> {
+        request.as_request()
+    }
+}
+
+/// Optional invoicing settings for a credit purchase, in owned form.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct OwnedCreditLedgerInvoiceSettingsRequestParams {
+    /// Whether the credits purchase invoice should auto collect with the customer's saved payment
+    /// method.
+    pub auto_collection: bool,
+    /// The difference between the invoice date and the issue date for the invoice. If due on issue,
+    /// set this to `0`.
+    pub net_terms: u64,
+    /// An optional memo to display on the invoice
+    pub memo: Option<String>,
+}
+
+/// An owned [`AddIncrementCreditLedgerEntryRequestParams`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedAddIncrementCreditLedgerEntryRequestParams {
+    /// The amount to credit the customer for.
+    pub amount: serde_json::Number,
+    /// An optional description for the credit operation.
+    pub description: Option<String>,
+    /// The date on which the block's balance will expire.
+    pub expiry_date: Option<OffsetDateTime>,
+    /// The date on which the block's balance will become available for use.
+    pub effective_date: Option<OffsetDateTime>,
+    /// The price per credit.
+    pub per_unit_cost_basis: Option<String>,
+    /// Invoicing settings for the credit increment request.
+    pub invoice_settings: Option<OwnedCreditLedgerInvoiceSettingsRequestParams>,
+}
+
+/// An owned [`AddVoidCreditLedgerEntryRequestParams`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedAddVoidCreditLedgerEntryRequestParams {
+    /// The number of credits to void.
+    pub amount: serde_json::Number,
+    /// The ID of the credit ledger block to void.
+    pub block_id: String,
+    /// An optional reason for the void.
+    pub void_reason: Option<VoidReason>,
+    /// An optional description for the void operation.
+    pub description: Option<String>,
+}
+
+/// A builder for an increment [`OwnedLedgerEntryRequest`].
+///
+/// The `amount` field is required.
+#[derive(Debug, Default, Clone)]
+pub struct IncrementLedgerEntryRequestBuilder {
+    amount: Option<serde_json::Number>,
+    description: Option<String>,
+    expiry_date: Option<OffsetDateTime>,
+    effective_date: Option<OffsetDateTime>,
+    per_unit_cost_basis: Option<String>,
+    invoice_settings: Option<OwnedCreditLedgerInvoiceSettingsRequestParams>,
+}
+
+impl IncrementLedgerEntryRequestBuilder {
+    /// Sets the amount to credit the customer for.
+    pub fn amount<N: Into<serde_json::Number>>(mut self, amount: N) -> Self {
+        self.amount = Some(amount.into());
+        self
+    }
+
+    /// Sets the description of the credit operation.
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the date on which the block's balance will expire.
+    pub fn expiry_date(mut self, expiry_date: OffsetDateTime) -> Self {
+        self.expiry_date = Some(expiry_date);
+        self
+    }
+
+    /// Sets the date on which the block's balance will become available for
+    /// use.
+    pub fn effective_date(mut self, effective_date: OffsetDateTime) -> Self {
+        self.effective_date = Some(effective_date);
+        self
+    }
+
+    /// Sets the price per credit.
+    pub fn per_unit_cost_basis<S: Into<String>>(mut self, per_unit_cost_basis: S) -> Self {
+        self.per_unit_cost_basis = Some(per_unit_cost_basis.into());
+        self
+    }
+
+    /// Sets the invoicing settings for the credit purchase.
+    pub fn invoice_settings(
+        mut self,
+        invoice_settings: OwnedCreditLedgerInvoiceSettingsRequestParams,
+    ) -> Self {
+        self.invoice_settings = Some(invoice_settings);
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// Returns [`Error::InvalidRequest`] if a required field is missing.
+    pub fn build(self) -> Result<OwnedLedgerEntryRequest, Error> {
+        let amount = require_some(self.amount, "amount")?;
+        Ok(OwnedLedgerEntryRequest::Increment(
+            OwnedAddIncrementCreditLedgerEntryRequestParams {
+                amount,
+                description: self.description,
+                expiry_date: self.expiry_date,
+                effective_date: self.effective_date,
+                per_unit_cost_basis: self.per_unit_cost_basis,
+                invoice_settings: self.invoice_settings,
+            },
+        ))
+    }
+}
+
+/// A builder for a void [`OwnedLedgerEntryRequest`].
+///
+/// The `amount` and `block_id` fields are required.
+#[derive(Debug, Default, Clone)]
+pub struct VoidLedgerEntryRequestBuilder {
+    amount: Option<serde_json::Number>,
+    block_id: String,
+    void_reason: Option<VoidReason>,
+    description: Option<String>,
+}
+
+impl VoidLedgerEntryRequestBuilder {
+    /// Sets the number of credits to void.
+    pub fn amount<N: Into<serde_json::Number>>(mut self, amount: N) -> Self {
+        self.amount = Some(amount.into());
+        self
+    }
+
+    /// Sets the ID of the credit ledger block to void.
+    pub fn block_id<S: Into<String>>(mut self, block_id: S) -> Self {
+        self.block_id = block_id.into();
+        self
+    }
+
+    /// Sets the reason for the void.
+    pub fn void_reason(mut self, void_reason: VoidReason) -> Self {
+        self.void_reason = Some(void_reason);
+        self
+    }
+
+    /// Sets the description of the void operation.
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// Returns [`Error::InvalidRequest`] if a required field is missing.
+    pub fn build(self) -> Result<OwnedLedgerEntryRequest, Error> {
+        let amount = require_some(self.amount, "amount")?;
+        require_field(&self.block_id, "block_id")?;
+        Ok(OwnedLedgerEntryRequest::Void(
+            OwnedAddVoidCreditLedgerEntryRequestParams {
+                amount,
+                block_id: self.block_id,
+                void_reason: self.void_reason,
+                description: self.description,
+            },
+        ))
+    }
+}
+
 /// A block of credit held by a customer.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct CustomerCreditBlock {
@@ -722,25 +1245,24 @@ impl Client {
     pub fn list_customers(
         &self,
         params: &ListParams,
-    ) -> impl Stream<Item = Result<Customer, Error>> + '_ {
+    ) -> impl Stream<Item = Result<Customer, Error>> + 'static {
         let req = self.build_request(Method::GET, CUSTOMERS_PATH);
         self.stream_paginated_request(params, req)
-            .try_filter_map(|res| async {
-                match res {
-                    CustomerResponse::Normal(c) => Ok(Some(c)),
-                    CustomerResponse::Deleted {
-                        id: _,
-                        deleted: true,
-                    } => Ok(None),
-                    CustomerResponse::Deleted { id, deleted: false } => {
-                        Err(Error::UnexpectedResponse {
-                            detail: format!(
-                                "customer {id} used deleted response shape \
-                                but deleted field was `false`"
-                            ),
-                        })
-                    }
-                }
+            .try_filter_map(|res: CustomerResponse| async { res.into_customer() })
+    }
+
+    /// Lists all customers, one page at a time.
+    ///
+    /// Each page reports the cursor for the next page, which can be passed to
+    /// [`ListParams::cursor`] to resume the listing from that page.
+    pub fn list_customers_pages(
+        &self,
+        params: &ListParams,
+    ) -> impl Stream<Item = Result<Page<Customer>, Error>> + 'static {
+        let req = self.build_request(Method::GET, CUSTOMERS_PATH);
+        self.stream_paginated_pages(params, req)
+            .and_then(|page: Page<CustomerResponse>| async {
+                page.try_filter_map(CustomerResponse::into_customer)
             })
     }
 
@@ -760,7 +1282,7 @@ impl Client {
 
     /// Gets a customer by ID.
     pub async fn get_customer(&self, id: &str) -> Result<Customer, Error> {
-        let req = self.build_request(Method::GET, CUSTOMERS_PATH.chain_one(id));
+        let req = self.build_request(Method::GET, CUSTOMERS_PATH.chain_id(id));
         let res = self.send_request(req).await?;
         Ok(res)
     }
@@ -771,7 +1293,7 @@ impl Client {
             Method::GET,
             CUSTOMERS_PATH
                 .chain_one("external_customer_id")
-                .chain_one(external_id),
+                .chain_id(external_id),
         );
         let res = self.send_request(req).await?;
         Ok(res)
@@ -783,7 +1305,7 @@ impl Client {
         id: &str,
         customer: &UpdateCustomerRequest<'_>,
     ) -> Result<Customer, Error> {
-        let req = self.build_request(Method::PUT, CUSTOMERS_PATH.chain_one(id));
+        let req = self.build_request(Method::PUT, CUSTOMERS_PATH.chain_id(id));
         let req = req.json(customer);
         let res = self.send_request(req).await?;
         Ok(res)
@@ -799,7 +1321,7 @@ impl Client {
             Method::PUT,
             CUSTOMERS_PATH
                 .chain_one("external_customer_id")
-                .chain_one(external_id),
+                .chain_id(external_id),
         );
         let req = req.json(customer);
         let res = self.send_request(req).await?;
@@ -808,7 +1330,7 @@ impl Client {
 
     /// Deletes a customer by ID.
     pub async fn delete_customer(&self, id: &str) -> Result<(), Error> {
-        let req = self.build_request(Method::DELETE, CUSTOMERS_PATH.chain_one(id));
+        let req = self.build_request(Method::DELETE, CUSTOMERS_PATH.chain_id(id));
         let _: Empty = self.send_request(req).await?;
         Ok(())
     }
@@ -821,10 +1343,10 @@ impl Client {
         &self,
         id: &str,
         params: &ListParams,
-    ) -> impl Stream<Item = Result<CustomerCreditBlock, Error>> + '_ {
+    ) -> impl Stream<Item = Result<CustomerCreditBlock, Error>> + 'static {
         let req = self.build_request(
             Method::GET,
-            CUSTOMERS_PATH.chain_one(id).chain_one("credits"),
+            CUSTOMERS_PATH.chain_id(id).chain_one("credits"),
         );
         self.stream_paginated_request(params, req)
     }
@@ -837,12 +1359,12 @@ impl Client {
         &self,
         external_id: &str,
         params: &ListParams,
-    ) -> impl Stream<Item = Result<CustomerCreditBlock, Error>> + '_ {
+    ) -> impl Stream<Item = Result<CustomerCreditBlock, Error>> + 'static {
         let req = self.build_request(
             Method::GET,
             CUSTOMERS_PATH
                 .chain_one("external_customer_id")
-                .chain_one(external_id)
+                .chain_id(external_id)
                 .chain_one("credits"),
         );
         self.stream_paginated_request(params, req)
@@ -857,7 +1379,7 @@ impl Client {
         let req = self.build_request(
             Method::POST,
             CUSTOMERS_PATH
-                .chain_one(id)
+                .chain_id(id)
                 .chain_one("credits")
                 .chain_one("ledger_entry"),
         );
@@ -871,7 +1393,7 @@ impl Client {
         id: &str,
         params: &CustomerCostParams<'_>,
     ) -> Result<Vec<CustomerCostBucket>, Error> {
-        let req = self.build_request(Method::GET, CUSTOMERS_PATH.chain_one(id).chain_one("costs"));
+        let req = self.build_request(Method::GET, CUSTOMERS_PATH.chain_id(id).chain_one("costs"));
         let req = req.apply(&params.filter);
         let res: ArrayResponse<CustomerCostBucket> = self.send_request(req).await?;
         Ok(res.data)
@@ -887,7 +1409,7 @@ impl Client {
             Method::GET,
             CUSTOMERS_PATH
                 .chain_one("external_customer_id")
-                .chain_one(external_id)
+                .chain_id(external_id)
                 .chain_one("costs"),
         );
         let req = req.apply(&params.filter);
@@ -895,3 +1417,136 @@ impl Client {
         Ok(res.data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::taxes::TaxIdType;
+
+    #[test]
+    fn create_customer_builder_validates_required_fields() {
+        let err = OwnedCreateCustomerRequest::builder()
+            .email("jane@example.com")
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidRequest { detail } if detail == "name is required"),
+            "{err}"
+        );
+
+        let err = OwnedCreateCustomerRequest::builder()
+            .name("Jane")
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidRequest { detail } if detail == "email is required"),
+            "{err}"
+        );
+
+        let request = OwnedCreateCustomerRequest::builder()
+            .external_id("ext")
+            .name("Jane")
+            .email("jane@example.com")
+            .additional_email("billing@example.com")
+            .payment_provider(PaymentProvider::Stripe, "cus_123")
+            .billing_address(OwnedAddressRequest {
+                city: Some("Boston".into()),
+                country: Some("US".into()),
+                ..Default::default()
+            })
+            .tax_id(OwnedTaxIdRequest {
+                type_: TaxIdType::UsEin,
+                value: "12-3456789".into(),
+                country: "US".into(),
+            })
+            .idempotency_key("key")
+            .build()
+            .unwrap();
+        let borrowed = request.as_request();
+        assert_eq!(borrowed.idempotency_key, Some("key"));
+        assert_eq!(
+            serde_json::to_value(borrowed).unwrap(),
+            serde_json::json!({
+                "external_customer_id": "ext",
+                "name": "Jane",
+                "email": "jane@example.com",
+                "additional_emails": ["billing@example.com"],
+                "payment_provider": "stripe",
+                "payment_provider_id": "cus_123",
+                "billing_address": {"city": "Boston", "country": "US"},
+                "tax_id": {"type": "us_ein", "value": "12-3456789", "country": "US"},
+            })
+        );
+    }
+
+    #[test]
+    fn increment_ledger_entry_builder_validates_required_fields() {
+        let err = OwnedLedgerEntryRequest::increment()
+            .description("credits")
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidRequest { detail } if detail == "amount is required"),
+            "{err}"
+        );
+
+        let request = OwnedLedgerEntryRequest::increment()
+            .amount(100)
+            .description("credits")
+            .expiry_date(OffsetDateTime::UNIX_EPOCH)
+            .invoice_settings(OwnedCreditLedgerInvoiceSettingsRequestParams {
+                auto_collection: true,
+                net_terms: 30,
+                memo: None,
+            })
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(request.as_request()).unwrap(),
+            serde_json::json!({
+                "entry_type": "increment",
+                "amount": 100,
+                "description": "credits",
+                "expiry_date": "1970-01-01T00:00:00Z",
+                "invoice_settings": {"auto_collection": true, "net_terms": 30},
+            })
+        );
+    }
+
+    #[test]
+    fn void_ledger_entry_builder_validates_required_fields() {
+        let err = OwnedLedgerEntryRequest::void()
+            .block_id("block")
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidRequest { detail } if detail == "amount is required"),
+            "{err}"
+        );
+
+        let err = OwnedLedgerEntryRequest::void()
+            .amount(5)
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidRequest { detail } if detail == "block_id is required"),
+            "{err}"
+        );
+
+        let request = OwnedLedgerEntryRequest::void()
+            .amount(5)
+            .block_id("block")
+            .void_reason(VoidReason::Refund)
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(request.as_request()).unwrap(),
+            serde_json::json!({
+                "entry_type": "void",
+                "amount": 5,
+                "block_id": "block",
+                "void_reason": "refund",
+            })
+        );
+    }
+}
@@ -13,22 +13,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::time::Duration;
 
+use async_stream::try_stream;
 use futures_core::Stream;
+use futures_util::{StreamExt, TryStreamExt};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::Number;
-use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+use uuid::Uuid;
 
-use crate::client::customers::CustomerId;
-use crate::client::Client;
-use crate::config::ListParams;
+use crate::client::customers::{CustomerId, OwnedCustomerId};
+use crate::client::{Client, Page, RequestBuilder};
+use crate::config::{ListParams, RequestOptions, RetryPolicy};
 use crate::error::Error;
-use crate::util::StrIteratorExt;
+use crate::metrics::IngestMetrics;
+use crate::util::{require_field, require_some, PathExt};
 
 const EVENTS_PATH: [&str; 1] = ["events"];
 
+/// The namespace from which every [`IdempotencyKeyNamespace`] is derived.
+const IDEMPOTENCY_KEY_ROOT: Uuid = Uuid::from_u128(0x5c1e_7d4a_93b2_4f08_a6d1_2e8b_70c4_f9a3);
+
 /// The subset of [`Event`] used in event ingestion requests.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct IngestEventRequest<'a> {
@@ -51,6 +61,191 @@ pub struct IngestEventRequest<'a> {
     pub timestamp: OffsetDateTime,
 }
 
+/// An owned [`IngestEventRequest`].
+///
+/// Unlike [`IngestEventRequest`], an owned request does not borrow its fields,
+/// so events can be built in one place and ingested later, e.g., from another
+/// task. Construct one with [`OwnedIngestEventRequest::builder`], and pass it
+/// to [`Client::ingest_events`] via [`as_request`].
+///
+/// [`as_request`]: OwnedIngestEventRequest::as_request
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct OwnedIngestEventRequest {
+    /// A unique, client-generated identifier for the event.
+    pub idempotency_key: String,
+    /// The customer with which the event is associated.
+    #[serde(flatten)]
+    pub customer_id: OwnedCustomerId,
+    /// A name that meaningfully identifies the action or event.
+    pub event_name: String,
+    /// Arbitrary properties associated with the event.
+    pub properties: BTreeMap<String, EventPropertyValue>,
+    /// The time at which the event occurred.
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+impl OwnedIngestEventRequest {
+    /// Returns a builder for an event ingestion request.
+    pub fn builder() -> IngestEventRequestBuilder {
+        IngestEventRequestBuilder::default()
+    }
+
+    /// Borrows this request as an [`IngestEventRequest`].
+    pub fn as_request(&self) -> IngestEventRequest<'_> {
+        IngestEventRequest {
+            idempotency_key: &self.idempotency_key,
+            customer_id: self.customer_id.as_id(),
+            event_name: &self.event_name,
+            properties: &self.properties,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedIngestEventRequest> for IngestEventRequest<'a> {
+    fn from(request: &'a OwnedIngestEventRequest) -> IngestEventRequest<'a> {
+        request.as_request()
+    }
+}
+
+/// A builder for an [`OwnedIngestEventRequest`].
+///
+/// The `idempotency_key`, `customer_id`, `event_name`, and `timestamp` fields
+/// are required.
+#[derive(Debug, Default, Clone)]
+pub struct IngestEventRequestBuilder {
+    idempotency_key: String,
+    customer_id: Option<OwnedCustomerId>,
+    event_name: String,
+    properties: BTreeMap<String, EventPropertyValue>,
+    timestamp: Option<OffsetDateTime>,
+    key_namespace: Option<IdempotencyKeyNamespace>,
+}
+
+impl IngestEventRequestBuilder {
+    /// Sets the unique, client-generated identifier for the event.
+    pub fn idempotency_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.idempotency_key = key.into();
+        self
+    }
+
+    /// Sets the customer with which the event is associated.
+    pub fn customer_id<I: Into<OwnedCustomerId>>(mut self, customer_id: I) -> Self {
+        self.customer_id = Some(customer_id.into());
+        self
+    }
+
+    /// Sets the name of the event.
+    pub fn event_name<S: Into<String>>(mut self, event_name: S) -> Self {
+        self.event_name = event_name.into();
+        self
+    }
+
+    /// Adds a property to the event.
+    pub fn property<K: Into<String>>(mut self, key: K, value: EventPropertyValue) -> Self {
+        self.properties.insert(key.into(), value);
+        self
+    }
+
+    /// Sets all of the event's properties, replacing any previously added.
+    pub fn properties(mut self, properties: BTreeMap<String, EventPropertyValue>) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    /// Sets the time at which the event occurred.
+    pub fn timestamp(mut self, timestamp: OffsetDateTime) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Derives the idempotency key from the content of the event in the
+    /// specified namespace when the request is built.
+    ///
+    /// The derived key replaces any key set via
+    /// [`IngestEventRequestBuilder::idempotency_key`]. See
+    /// [`IdempotencyKeyNamespace`] for details.
+    pub fn derive_idempotency_key(mut self, namespace: IdempotencyKeyNamespace) -> Self {
+        self.key_namespace = Some(namespace);
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// Returns [`Error::InvalidRequest`] if a required field is missing.
+    pub fn build(self) -> Result<OwnedIngestEventRequest, Error> {
+        if self.key_namespace.is_none() {
+            require_field(&self.idempotency_key, "idempotency_key")?;
+        }
+        let customer_id = require_some(self.customer_id, "customer_id")?;
+        require_field(customer_id.id(), "customer_id")?;
+        require_field(&self.event_name, "event_name")?;
+        let timestamp = require_some(self.timestamp, "timestamp")?;
+        let mut request = OwnedIngestEventRequest {
+            idempotency_key: self.idempotency_key,
+            customer_id,
+            event_name: self.event_name,
+            properties: self.properties,
+            timestamp,
+        };
+        if let Some(namespace) = self.key_namespace {
+            request.idempotency_key = namespace.derive_key(&request.as_request());
+        }
+        Ok(request)
+    }
+}
+
+/// A namespace for idempotency keys derived from the content of events.
+///
+/// A key derived from an event's content, rather than generated at random,
+/// is the same every time the same logical event is ingested, so
+/// re-ingesting the event, e.g., when replaying events from a data
+/// warehouse, is a no-op. The key is derived from the customer ID, the event
+/// name, the timestamp, and the properties of the event, and is stable
+/// across releases of this crate. Two events that differ in any of these
+/// fields have different keys, as do the same event in two namespaces.
+///
+/// Derive keys via [`IdempotencyKeyNamespace::derive_key`],
+/// [`IngestEventRequestBuilder::derive_idempotency_key`], or
+/// [`IngesterConfig::idempotency_keys`](crate::IngesterConfig::idempotency_keys).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IdempotencyKeyNamespace {
+    namespace: Uuid,
+}
+
+impl IdempotencyKeyNamespace {
+    /// Creates the namespace identified by the specified salt.
+    pub fn new(salt: &str) -> IdempotencyKeyNamespace {
+        IdempotencyKeyNamespace {
+            namespace: Uuid::new_v5(&IDEMPOTENCY_KEY_ROOT, salt.as_bytes()),
+        }
+    }
+
+    /// Derives the idempotency key for an event.
+    ///
+    /// The event's own idempotency key is ignored. Timestamps that denote
+    /// the same instant derive the same key regardless of their offset.
+    pub fn derive_key(&self, event: &IngestEventRequest) -> String {
+        let (id_kind, id) = match event.customer_id {
+            CustomerId::Orb(id) => ("customer_id", id),
+            CustomerId::External(id) => ("external_customer_id", id),
+        };
+        // The properties serialize in key order, so the serialization is
+        // canonical.
+        let content = serde_json::to_vec(&(
+            id_kind,
+            id,
+            event.event_name,
+            event.timestamp.unix_timestamp(),
+            event.timestamp.nanosecond(),
+            event.properties,
+        ))
+        .expect("event content is serializable");
+        Uuid::new_v5(&self.namespace, &content).to_string()
+    }
+}
+
 /// The ingestion mode.
 #[derive(Debug, Clone, Copy)]
 pub enum IngestionMode {
@@ -63,12 +258,54 @@ pub enum IngestionMode {
 /// The response to an event ingestion request.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
 pub struct IngestEventResponse {
+    /// The events that Orb reported as having failed validation, which were
+    /// not ingested.
+    ///
+    /// Orb ingests the remaining events in the request even if some fail
+    /// validation.
+    #[serde(default)]
+    pub validation_failed: Vec<IngestValidationFailure>,
+    /// The events that failed [client-side
+    /// validation](crate::ClientBuilder::event_validation), by idempotency
+    /// key, which were not sent to Orb.
+    #[serde(skip)]
+    pub locally_rejected: Vec<(String, EventValidationError)>,
     /// Debug information.
     ///
     /// Only present when the request is made with [`IngestionMode::Debug`].
     pub debug: Option<IngestEventDebugResponse>,
 }
 
+/// An event that failed validation. See
+/// [`IngestEventResponse::validation_failed`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub struct IngestValidationFailure {
+    /// The idempotency key of the event.
+    pub idempotency_key: String,
+    /// The reasons that the event failed validation.
+    pub validation_errors: Vec<String>,
+}
+
+/// The outcome of ingesting a single event. See
+/// [`Client::ingest_events_with_outcomes`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IngestEventOutcome {
+    /// The event was new to Orb and was ingested.
+    Ingested,
+    /// The event was already known to Orb.
+    Duplicate,
+    /// Orb reported that the event failed validation, and did not ingest
+    /// it.
+    Rejected {
+        /// The reasons that the event failed validation.
+        validation_errors: Vec<String>,
+    },
+    /// The event failed [client-side
+    /// validation](crate::ClientBuilder::event_validation) and was not sent
+    /// to Orb.
+    RejectedLocally(EventValidationError),
+}
+
 /// The type of [`IngestEventResponse::debug`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
 pub struct IngestEventDebugResponse {
@@ -95,6 +332,309 @@ pub struct AmendEventRequest<'a> {
     pub timestamp: OffsetDateTime,
 }
 
+/// An owned [`AmendEventRequest`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct OwnedAmendEventRequest {
+    /// The customer with which the event is associated.
+    #[serde(flatten)]
+    pub customer_id: OwnedCustomerId,
+    /// A name that meaningfully identifies the action or event.
+    pub event_name: String,
+    /// Arbitrary properties associated with the event.
+    pub properties: BTreeMap<String, EventPropertyValue>,
+    /// The time at which the event occurred.
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+impl OwnedAmendEventRequest {
+    /// Borrows this request as an [`AmendEventRequest`].
+    pub fn as_request(&self) -> AmendEventRequest<'_> {
+        AmendEventRequest {
+            customer_id: self.customer_id.as_id(),
+            event_name: &self.event_name,
+            properties: &self.properties,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedAmendEventRequest> for AmendEventRequest<'a> {
+    fn from(request: &'a OwnedAmendEventRequest) -> AmendEventRequest<'a> {
+        request.as_request()
+    }
+}
+
+/// The response to an event amendment request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub struct AmendEventResponse {
+    /// The ID of the amended event.
+    pub amended: String,
+}
+
+/// The response to an event deprecation request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub struct DeprecateEventResponse {
+    /// The ID of the deprecated event.
+    pub deprecated: String,
+}
+
+impl IngestEventRequest<'_> {
+    /// Validates the event against the default [`EventValidationRules`].
+    ///
+    /// Reports every problem with the event at once.
+    pub fn validate(&self) -> Result<(), EventValidationError> {
+        self.validate_with(&EventValidationRules::DEFAULT)
+    }
+
+    /// Validates the event against the specified rules.
+    pub fn validate_with(&self, rules: &EventValidationRules) -> Result<(), EventValidationError> {
+        self.validate_at(rules, OffsetDateTime::now_utc())
+    }
+
+    pub(crate) fn validate_at(
+        &self,
+        rules: &EventValidationRules,
+        now: OffsetDateTime,
+    ) -> Result<(), EventValidationError> {
+        let mut issues = vec![];
+        if self.idempotency_key.is_empty() {
+            issues.push(EventValidationIssue::EmptyIdempotencyKey);
+        }
+        rules.check(
+            &mut issues,
+            &self.customer_id,
+            self.event_name,
+            self.properties,
+            self.timestamp,
+            now,
+        );
+        if self.timestamp < now - rules.grace_period {
+            issues.push(EventValidationIssue::TimestampTooOld {
+                timestamp: self.timestamp,
+            });
+        }
+        EventValidationError::from_issues(issues)
+    }
+}
+
+impl AmendEventRequest<'_> {
+    /// Validates the event against the default [`EventValidationRules`].
+    ///
+    /// Reports every problem with the event at once. Amendments may apply to
+    /// events of any age, so the grace period is not checked.
+    pub fn validate(&self) -> Result<(), EventValidationError> {
+        self.validate_with(&EventValidationRules::DEFAULT)
+    }
+
+    /// Validates the event against the specified rules, except for the grace
+    /// period.
+    pub fn validate_with(&self, rules: &EventValidationRules) -> Result<(), EventValidationError> {
+        let mut issues = vec![];
+        rules.check(
+            &mut issues,
+            &self.customer_id,
+            self.event_name,
+            self.properties,
+            self.timestamp,
+            OffsetDateTime::now_utc(),
+        );
+        EventValidationError::from_issues(issues)
+    }
+}
+
+/// Rules for client-side validation of events.
+///
+/// See [`IngestEventRequest::validate_with`] and
+/// [`ClientBuilder::event_validation`].
+///
+/// [`ClientBuilder::event_validation`]: crate::ClientBuilder::event_validation
+#[derive(Debug, Clone)]
+pub struct EventValidationRules {
+    max_properties: usize,
+    max_property_key_len: usize,
+    max_future_skew: Duration,
+    grace_period: Duration,
+}
+
+impl Default for EventValidationRules {
+    fn default() -> EventValidationRules {
+        EventValidationRules::DEFAULT
+    }
+}
+
+impl EventValidationRules {
+    /// The default validation rules.
+    ///
+    /// Permits up to 100 properties with keys of up to 256 bytes, and
+    /// timestamps from 12 hours in the past, Orb's default grace period for
+    /// late events, to 5 minutes in the future.
+    ///
+    /// Exposed as a constant for use in constant evaluation contexts.
+    pub const DEFAULT: EventValidationRules = EventValidationRules {
+        max_properties: 100,
+        max_property_key_len: 256,
+        max_future_skew: Duration::from_secs(5 * 60),
+        grace_period: Duration::from_secs(12 * 60 * 60),
+    };
+
+    /// Sets the maximum number of properties on an event.
+    pub const fn max_properties(mut self, max_properties: usize) -> Self {
+        self.max_properties = max_properties;
+        self
+    }
+
+    /// Sets the maximum length of a property key, in bytes.
+    pub const fn max_property_key_len(mut self, max_property_key_len: usize) -> Self {
+        self.max_property_key_len = max_property_key_len;
+        self
+    }
+
+    /// Sets how far in the future an event's timestamp may be, to allow for
+    /// clock skew.
+    pub const fn max_future_skew(mut self, max_future_skew: Duration) -> Self {
+        self.max_future_skew = max_future_skew;
+        self
+    }
+
+    /// Sets how far in the past an ingested event's timestamp may be.
+    ///
+    /// Set this to match the grace period configured for your Orb account.
+    /// Amendments are not subject to the grace period.
+    pub const fn grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Checks the rules that apply to both ingested and amended events.
+    fn check(
+        &self,
+        issues: &mut Vec<EventValidationIssue>,
+        customer_id: &CustomerId<'_>,
+        event_name: &str,
+        properties: &BTreeMap<String, EventPropertyValue>,
+        timestamp: OffsetDateTime,
+        now: OffsetDateTime,
+    ) {
+        if let CustomerId::Orb("") | CustomerId::External("") = customer_id {
+            issues.push(EventValidationIssue::EmptyCustomerId);
+        }
+        if event_name.is_empty() {
+            issues.push(EventValidationIssue::EmptyEventName);
+        }
+        if properties.len() > self.max_properties {
+            issues.push(EventValidationIssue::TooManyProperties {
+                count: properties.len(),
+                max: self.max_properties,
+            });
+        }
+        for key in properties.keys() {
+            if key.len() > self.max_property_key_len {
+                issues.push(EventValidationIssue::PropertyKeyTooLong {
+                    key: key.clone(),
+                    max: self.max_property_key_len,
+                });
+            }
+        }
+        if timestamp > now + self.max_future_skew {
+            issues.push(EventValidationIssue::TimestampInFuture { timestamp });
+        }
+    }
+}
+
+/// A problem with an event found by client-side validation.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EventValidationIssue {
+    /// The idempotency key is empty.
+    EmptyIdempotencyKey,
+    /// The customer ID is empty.
+    EmptyCustomerId,
+    /// The event name is empty.
+    EmptyEventName,
+    /// The event has too many properties.
+    TooManyProperties {
+        /// The number of properties on the event.
+        count: usize,
+        /// The maximum number of properties.
+        max: usize,
+    },
+    /// A property key is too long.
+    PropertyKeyTooLong {
+        /// The property key.
+        key: String,
+        /// The maximum length of a property key, in bytes.
+        max: usize,
+    },
+    /// The timestamp is too far in the future.
+    TimestampInFuture {
+        /// The event's timestamp.
+        timestamp: OffsetDateTime,
+    },
+    /// The timestamp is older than the grace period.
+    TimestampTooOld {
+        /// The event's timestamp.
+        timestamp: OffsetDateTime,
+    },
+}
+
+impl fmt::Display for EventValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventValidationIssue::EmptyIdempotencyKey => f.write_str("idempotency key is empty"),
+            EventValidationIssue::EmptyCustomerId => f.write_str("customer ID is empty"),
+            EventValidationIssue::EmptyEventName => f.write_str("event name is empty"),
+            EventValidationIssue::TooManyProperties { count, max } => {
+                write!(
+                    f,
+                    "event has {count} properties, more than the maximum of {max}"
+                )
+            }
+            EventValidationIssue::PropertyKeyTooLong { key, max } => {
+                write!(f, "property key {key:?} is longer than {max} bytes")
+            }
+            EventValidationIssue::TimestampInFuture { timestamp } => {
+                write!(f, "timestamp {timestamp} is too far in the future")
+            }
+            EventValidationIssue::TimestampTooOld { timestamp } => {
+                write!(f, "timestamp {timestamp} is older than the grace period")
+            }
+        }
+    }
+}
+
+/// The problems with an event found by client-side validation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventValidationError {
+    /// The problems with the event. Never empty.
+    pub issues: Vec<EventValidationIssue>,
+}
+
+impl EventValidationError {
+    fn from_issues(issues: Vec<EventValidationIssue>) -> Result<(), EventValidationError> {
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(EventValidationError { issues })
+        }
+    }
+}
+
+impl fmt::Display for EventValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid event: ")?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for EventValidationError {}
+
 /// An Orb event.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
 pub struct Event {
@@ -172,7 +712,26 @@ impl<'a> EventSearchParams<'a> {
     ///
     /// See [`ListParams::page_size`].
     pub const fn page_size(mut self, page_size: u64) -> Self {
-        self.inner = self.inner.page_size(page_size);
+        self.inner.page_size = page_size;
+        self
+    }
+
+    /// Sets the number of pages to fetch ahead of the consumer.
+    ///
+    /// See [`ListParams::prefetch`].
+    pub const fn prefetch(mut self, prefetch: usize) -> Self {
+        self.inner.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the cursor from which to start the list operation.
+    ///
+    /// See [`ListParams::cursor`].
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.inner = self.inner.cursor(cursor);
         self
     }
 
@@ -201,18 +760,374 @@ impl<'a> EventSearchParams<'a> {
     }
 }
 
+/// The number of events ingested in a time bucket. See
+/// [`Client::get_event_volume`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub struct EventVolume {
+    /// The number of events whose timestamps fall in the bucket.
+    pub count: u64,
+    /// The (inclusive) start of the bucket.
+    #[serde(with = "time::serde::rfc3339")]
+    pub timeframe_start: OffsetDateTime,
+    /// The (exclusive) end of the bucket.
+    #[serde(with = "time::serde::rfc3339")]
+    pub timeframe_end: OffsetDateTime,
+}
+
+/// Parameters for an event volume query.
+#[derive(Debug, Clone)]
+pub struct EventVolumeParams {
+    inner: ListParams,
+    timeframe_start: Option<OffsetDateTime>,
+    timeframe_end: Option<OffsetDateTime>,
+}
+
+impl Default for EventVolumeParams {
+    fn default() -> EventVolumeParams {
+        EventVolumeParams::DEFAULT
+    }
+}
+
+impl EventVolumeParams {
+    /// The default event volume parameters.
+    ///
+    /// Exposed as a constant for use in constant evaluation contexts.
+    pub const DEFAULT: EventVolumeParams = EventVolumeParams {
+        inner: ListParams::DEFAULT,
+        timeframe_start: None,
+        timeframe_end: None,
+    };
+
+    /// Sets the page size for the list operation.
+    ///
+    /// See [`ListParams::page_size`].
+    pub const fn page_size(mut self, page_size: u64) -> Self {
+        self.inner.page_size = page_size;
+        self
+    }
+
+    /// Sets the number of pages to fetch ahead of the consumer.
+    ///
+    /// See [`ListParams::prefetch`].
+    pub const fn prefetch(mut self, prefetch: usize) -> Self {
+        self.inner.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the cursor from which to start the list operation.
+    ///
+    /// See [`ListParams::cursor`].
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.inner = self.inner.cursor(cursor);
+        self
+    }
+
+    /// Reports the volume of events falling on or after the specified
+    /// datetime.
+    ///
+    /// Required by Orb. If the datetime is not aligned to an hour, the volume
+    /// of the hour in which it falls is reported.
+    pub const fn timeframe_start(mut self, start: OffsetDateTime) -> Self {
+        self.timeframe_start = Some(start);
+        self
+    }
+
+    /// Reports the volume of events falling before the specified datetime.
+    ///
+    /// If not specified, Orb uses the current time.
+    pub const fn timeframe_end(mut self, end: OffsetDateTime) -> Self {
+        self.timeframe_end = Some(end);
+        self
+    }
+}
+
+/// Configures how [`Client::search_events_range`] splits a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventSearchRangeConfig {
+    max_window: Duration,
+    max_event_ids: usize,
+    concurrency: usize,
+}
+
+impl Default for EventSearchRangeConfig {
+    fn default() -> EventSearchRangeConfig {
+        EventSearchRangeConfig::DEFAULT
+    }
+}
+
+impl EventSearchRangeConfig {
+    /// The default range search configuration.
+    ///
+    /// Searches windows of at most ten days and at most 500 event IDs, and
+    /// runs up to four searches at once.
+    ///
+    /// Exposed as a constant for use in constant evaluation contexts.
+    pub const DEFAULT: EventSearchRangeConfig = EventSearchRangeConfig {
+        max_window: Duration::from_secs(10 * 24 * 60 * 60),
+        max_event_ids: 500,
+        concurrency: 4,
+    };
+
+    /// Sets the maximum width of the timeframe of a single search.
+    ///
+    /// A window shorter than one second is treated as one second.
+    pub const fn max_window(mut self, max_window: Duration) -> Self {
+        self.max_window = if max_window.as_secs() == 0 {
+            Duration::from_secs(1)
+        } else {
+            max_window
+        };
+        self
+    }
+
+    /// Sets the maximum number of event IDs in a single search.
+    ///
+    /// Zero is treated as one.
+    pub const fn max_event_ids(mut self, max_event_ids: usize) -> Self {
+        self.max_event_ids = if max_event_ids == 0 { 1 } else { max_event_ids };
+        self
+    }
+
+    /// Sets the maximum number of searches to run at once.
+    ///
+    /// Zero is treated as one.
+    pub const fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = if concurrency == 0 { 1 } else { concurrency };
+        self
+    }
+
+    /// Splits the timeframe from `start` to `end` into consecutive windows.
+    ///
+    /// A timeframe that is unbounded on either side is not split, so the
+    /// caller must bound the end of the timeframe if it is to be split.
+    fn windows(
+        &self,
+        start: Option<OffsetDateTime>,
+        end: Option<OffsetDateTime>,
+    ) -> Vec<(Option<OffsetDateTime>, Option<OffsetDateTime>)> {
+        let (Some(mut start), Some(end)) = (start, end) else {
+            return vec![(start, end)];
+        };
+        let max_window = time::Duration::try_from(self.max_window).unwrap_or(time::Duration::MAX);
+        let mut windows = vec![];
+        loop {
+            let window_end = start.saturating_add(max_window);
+            if window_end >= end {
+                windows.push((Some(start), Some(end)));
+                return windows;
+            }
+            windows.push((Some(start), Some(window_end)));
+            start = window_end;
+        }
+    }
+}
+
+/// Configures [`Client::amend_events`] and [`Client::deprecate_events`].
+#[derive(Debug, Clone)]
+pub struct BulkEventConfig {
+    concurrency: usize,
+    retry_policy: RetryPolicy,
+}
+
+impl Default for BulkEventConfig {
+    fn default() -> BulkEventConfig {
+        BulkEventConfig::DEFAULT
+    }
+}
+
+impl BulkEventConfig {
+    /// The default bulk operation configuration.
+    ///
+    /// Updates up to eight events at once, and retries each update according
+    /// to [`RetryPolicy::DEFAULT`].
+    ///
+    /// Exposed as a constant for use in constant evaluation contexts.
+    pub const DEFAULT: BulkEventConfig = BulkEventConfig {
+        concurrency: 8,
+        retry_policy: RetryPolicy::DEFAULT,
+    };
+
+    /// Sets the maximum number of events to update at once.
+    ///
+    /// Zero is treated as one.
+    pub const fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = if concurrency == 0 { 1 } else { concurrency };
+        self
+    }
+
+    /// Sets the policy for retrying updates that fail.
+    ///
+    /// Overrides the client's retry policy for the updates. Only failures
+    /// that are [retryable](Error::is_retryable) are retried.
+    pub const fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+}
+
+/// The result of updating a single event in a bulk operation. See
+/// [`Client::amend_events`] and [`Client::deprecate_events`].
+#[derive(Debug)]
+pub struct BulkEventResult<T> {
+    /// The ID of the event.
+    pub id: String,
+    /// The response from Orb, or the error from the final attempt to update
+    /// the event.
+    pub result: Result<T, Error>,
+}
+
+/// Orders events by timestamp, breaking ties by ID.
+fn by_timestamp(a: &Event, b: &Event) -> std::cmp::Ordering {
+    (a.timestamp, &a.id).cmp(&(b.timestamp, &b.id))
+}
+
 impl Client {
     /// Searches events.
     pub fn search_events(
         &self,
         params: &EventSearchParams,
-    ) -> impl Stream<Item = Result<Event, Error>> + '_ {
+    ) -> impl Stream<Item = Result<Event, Error>> + 'static {
+        let req = self.build_request(Method::POST, EVENTS_PATH.chain_one("search"));
+        let req = req.json(&params.filter);
+        self.stream_paginated_request(&params.inner, req)
+    }
+
+    /// Searches events, one page at a time.
+    ///
+    /// Each page reports the cursor for the next page, which can be passed to
+    /// [`EventSearchParams::cursor`] to resume the search from that page.
+    pub fn search_events_pages(
+        &self,
+        params: &EventSearchParams,
+    ) -> impl Stream<Item = Result<Page<Event>, Error>> + 'static {
         let req = self.build_request(Method::POST, EVENTS_PATH.chain_one("search"));
         let req = req.json(&params.filter);
+        self.stream_paginated_pages(&params.inner, req)
+    }
+
+    /// Gets the volume of ingested events, aggregated by hour, as configured
+    /// by `params`.
+    ///
+    /// An event is counted in the hour in which its timestamp falls.
+    pub fn get_event_volume(
+        &self,
+        params: &EventVolumeParams,
+    ) -> impl Stream<Item = Result<EventVolume, Error>> + 'static {
+        let req = self.get_event_volume_request(params);
         self.stream_paginated_request(&params.inner, req)
     }
 
+    /// Gets the volume of ingested events, one page at a time.
+    ///
+    /// Each page reports the cursor for the next page, which can be passed to
+    /// [`EventVolumeParams::cursor`] to resume the query from that page.
+    pub fn get_event_volume_pages(
+        &self,
+        params: &EventVolumeParams,
+    ) -> impl Stream<Item = Result<Page<EventVolume>, Error>> + 'static {
+        let req = self.get_event_volume_request(params);
+        self.stream_paginated_pages(&params.inner, req)
+    }
+
+    fn get_event_volume_request(&self, params: &EventVolumeParams) -> RequestBuilder {
+        let mut req = self.build_request(Method::GET, EVENTS_PATH.chain_one("volume"));
+        for (name, filter) in [
+            ("timeframe_start", params.timeframe_start),
+            ("timeframe_end", params.timeframe_end),
+        ] {
+            if let Some(datetime) = filter {
+                // Orb requires supplied datetimes be in UTC
+                let datetime = datetime.to_offset(UtcOffset::UTC).format(&Rfc3339).unwrap();
+                req = req.query(&[(name, datetime)]);
+            }
+        }
+        req
+    }
+
+    /// Searches events over a timeframe of any width.
+    ///
+    /// Orb limits the width of the timeframe and the number of event IDs in a
+    /// single search. This method splits the timeframe into consecutive
+    /// windows and the event IDs into chunks that respect the limits set by
+    /// `config`, runs a search for every combination, and merges the results
+    /// into a single stream of events in timestamp order. A timeframe without
+    /// an end is taken to end at the current time. A timeframe without a
+    /// start is not split.
+    ///
+    /// Up to [`EventSearchRangeConfig::concurrency`] searches run at once,
+    /// and the events of every window are held in memory until all earlier
+    /// windows have been yielded. The cursor of `params` is ignored.
+    pub fn search_events_range(
+        &self,
+        params: &EventSearchParams,
+        config: &EventSearchRangeConfig,
+    ) -> impl Stream<Item = Result<Event, Error>> + 'static {
+        let filter = &params.filter;
+        let list_params = ListParams {
+            cursor: None,
+            ..params.inner.clone()
+        };
+        let id_chunks: Vec<Option<&[&str]>> = match filter.event_ids {
+            Some(ids) if !ids.is_empty() => ids.chunks(config.max_event_ids).map(Some).collect(),
+            ids => vec![ids],
+        };
+        // Bound an open-ended timeframe, so that it can be split too.
+        let timeframe_end = match (filter.timeframe_start, filter.timeframe_end) {
+            (Some(_), None) => Some(OffsetDateTime::now_utc()),
+            (_, end) => end,
+        };
+        let mut searches = vec![];
+        for (i, (start, end)) in config
+            .windows(filter.timeframe_start, timeframe_end)
+            .into_iter()
+            .enumerate()
+        {
+            for event_ids in &id_chunks {
+                let req = self.build_request(Method::POST, EVENTS_PATH.chain_one("search"));
+                let req = req.json(&EventFilter {
+                    event_ids: *event_ids,
+                    invoice_id: filter.invoice_id,
+                    timeframe_start: start,
+                    timeframe_end: end,
+                });
+                searches.push((i, self.stream_paginated_request(&list_params, req)));
+            }
+        }
+        // `buffered` yields results in the order of the searches, so the
+        // searches of each window arrive consecutively.
+        let searches = futures_util::stream::iter(searches).map(|(window, events)| async move {
+            (window, events.try_collect::<Vec<Event>>().await)
+        });
+        let mut results = searches.buffered(config.concurrency);
+        try_stream! {
+            let mut current = None;
+            let mut window_events = vec![];
+            while let Some((window, events)) = results.next().await {
+                if current != Some(window) {
+                    window_events.sort_by(by_timestamp);
+                    for event in window_events.drain(..) {
+                        yield event;
+                    }
+                    current = Some(window);
+                }
+                window_events.extend(events?);
+            }
+            window_events.sort_by(by_timestamp);
+            for event in window_events {
+                yield event;
+            }
+        }
+    }
+
     /// Ingests events.
+    ///
+    /// If the client is configured with [event
+    /// validation](crate::ClientBuilder::event_validation), invalid events
+    /// are not sent, and are instead reported in
+    /// [`IngestEventResponse::locally_rejected`].
     pub async fn ingest_events(
         &self,
         mode: IngestionMode,
@@ -231,26 +1146,649 @@ impl Client {
         } else {
             req
         };
-        let req = req.json(&Envelope { events });
-        let res = self.send_request(req).await?;
+        // Reject invalid events locally, if configured to.
+        let submitted = events.len();
+        let mut rejected = vec![];
+        let valid: Vec<_>;
+        let events = match &self.inner.event_validation {
+            None => events,
+            Some(rules) => {
+                let now = OffsetDateTime::now_utc();
+                let mut accepted = vec![];
+                for event in events {
+                    match event.validate_at(rules, now) {
+                        Ok(()) => accepted.push(event.clone()),
+                        Err(e) => rejected.push((event.idempotency_key.into(), e)),
+                    }
+                }
+                valid = accepted;
+                &valid
+            }
+        };
+        let mut res = if events.is_empty() && !rejected.is_empty() {
+            IngestEventResponse {
+                validation_failed: vec![],
+                locally_rejected: vec![],
+                debug: matches!(mode, IngestionMode::Debug).then(|| IngestEventDebugResponse {
+                    duplicate: vec![],
+                    ingested: vec![],
+                }),
+            }
+        } else {
+            let req = req.json(&Envelope { events });
+            self.send_request(req).await?
+        };
+        res.locally_rejected = rejected;
+        self.observe_ingest(IngestMetrics {
+            events: submitted,
+            accepted: res.debug.as_ref().map(|d| d.ingested.len()),
+            duplicate: res.debug.as_ref().map(|d| d.duplicate.len()),
+            rejected: res.validation_failed.len() + res.locally_rejected.len(),
+        });
         Ok(res)
     }
 
+    /// Ingests events and reports the outcome of each event.
+    ///
+    /// The request is made with [`IngestionMode::Debug`], so that Orb reports
+    /// which events were duplicates. The returned outcomes are in the same
+    /// order as `events`. An event that Orb reports as neither a duplicate
+    /// nor a validation failure is reported as ingested.
+    pub async fn ingest_events_with_outcomes(
+        &self,
+        backfill_id: Option<String>,
+        events: &[IngestEventRequest<'_>],
+    ) -> Result<Vec<IngestEventOutcome>, Error> {
+        let res = self
+            .ingest_events(IngestionMode::Debug, backfill_id, events)
+            .await?;
+        let mut rejected: BTreeMap<_, _> = res
+            .validation_failed
+            .into_iter()
+            .map(|f| (f.idempotency_key, f.validation_errors))
+            .collect();
+        let mut locally_rejected: BTreeMap<_, _> = res.locally_rejected.into_iter().collect();
+        let duplicate: BTreeSet<_> = res
+            .debug
+            .map(|d| d.duplicate)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let outcomes = events
+            .iter()
+            .map(|event| {
+                if let Some(error) = locally_rejected.remove(event.idempotency_key) {
+                    IngestEventOutcome::RejectedLocally(error)
+                } else if let Some(validation_errors) = rejected.remove(event.idempotency_key) {
+                    IngestEventOutcome::Rejected { validation_errors }
+                } else if duplicate.contains(event.idempotency_key) {
+                    IngestEventOutcome::Duplicate
+                } else {
+                    IngestEventOutcome::Ingested
+                }
+            })
+            .collect();
+        Ok(outcomes)
+    }
+
     /// Amends an event by ID.
-    pub async fn amend_event(&self, id: &str, event: &AmendEventRequest<'_>) -> Result<(), Error> {
-        let req = self.build_request(Method::PUT, EVENTS_PATH.chain_one(id));
+    pub async fn amend_event(
+        &self,
+        id: &str,
+        event: &AmendEventRequest<'_>,
+    ) -> Result<AmendEventResponse, Error> {
+        let req = self.build_request(Method::PUT, EVENTS_PATH.chain_id(id));
         let req = req.json(event);
-        let _: serde_json::Value = self.send_request(req).await?;
-        Ok(())
+        self.send_request(req).await
     }
 
     /// Deprecates an event by ID.
-    pub async fn deprecate_event(&self, id: &str) -> Result<(), Error> {
-        let req = self.build_request(
-            Method::PUT,
-            EVENTS_PATH.chain_one(id).chain_one("deprecate"),
+    pub async fn deprecate_event(&self, id: &str) -> Result<DeprecateEventResponse, Error> {
+        let req = self.build_request(Method::PUT, EVENTS_PATH.chain_id(id).chain_one("deprecate"));
+        self.send_request(req).await
+    }
+
+    /// Returns a handle through which to update the event `id` as part of
+    /// the bulk operation `operation`.
+    ///
+    /// Each update carries an idempotency key derived from the operation, the
+    /// action, and the event ID, so that the client can safely retry it
+    /// according to the configured retry policy.
+    fn bulk_update_client(
+        &self,
+        config: &BulkEventConfig,
+        operation: Uuid,
+        action: &str,
+        id: &str,
+    ) -> Client {
+        let options = RequestOptions::new()
+            .idempotency_key(format!("{operation}-{action}-{id}"))
+            .retry_policy(config.retry_policy.clone());
+        self.with_options(options)
+    }
+
+    /// Amends many events.
+    ///
+    /// Each item of `amendments` pairs the ID of an event with its amended
+    /// contents. Up to [`BulkEventConfig::concurrency`] events are amended at
+    /// once, and failed amendments are retried according to
+    /// [`BulkEventConfig::retry_policy`]. Returns the result of every
+    /// amendment, in the order of `amendments`.
+    pub async fn amend_events<S>(
+        &self,
+        amendments: S,
+        config: &BulkEventConfig,
+    ) -> Vec<BulkEventResult<AmendEventResponse>>
+    where
+        S: Stream<Item = (String, OwnedAmendEventRequest)>,
+    {
+        let operation = Uuid::new_v4();
+        amendments
+            .map(|(id, event)| {
+                let client = self.bulk_update_client(config, operation, "amend", &id);
+                async move {
+                    let request = event.as_request();
+                    let result = client.amend_event(&id, &request).await;
+                    BulkEventResult { id, result }
+                }
+            })
+            .buffered(config.concurrency)
+            .collect()
+            .await
+    }
+
+    /// Deprecates many events by ID.
+    ///
+    /// Up to [`BulkEventConfig::concurrency`] events are deprecated at once.
+    /// Returns the result of every deprecation, in the order of `ids`.
+    ///
+    /// Failed deprecations are retried according to
+    /// [`BulkEventConfig::retry_policy`].
+    pub async fn deprecate_events<S>(
+        &self,
+        ids: S,
+        config: &BulkEventConfig,
+    ) -> Vec<BulkEventResult<DeprecateEventResponse>>
+    where
+        S: Stream<Item = String>,
+    {
+        let operation = Uuid::new_v4();
+        ids.map(|id| {
+            let client = self.bulk_update_client(config, operation, "deprecate", &id);
+            async move {
+                let result = client.deprecate_event(&id).await;
+                BulkEventResult { id, result }
+            }
+        })
+        .buffered(config.concurrency)
+        .collect()
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::mock::{test_event, MockTransport};
+
+    #[test]
+    fn ingest_event_builder_validates_required_fields() {
+        let err = OwnedIngestEventRequest::builder()
+            .idempotency_key("key")
+            .event_name("api_call")
+            .timestamp(OffsetDateTime::UNIX_EPOCH)
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidRequest { detail } if detail == "customer_id is required"),
+            "{err}"
+        );
+
+        let event = OwnedIngestEventRequest::builder()
+            .idempotency_key("key")
+            .customer_id(CustomerId::External("ext"))
+            .event_name("api_call")
+            .property("count", EventPropertyValue::Number(1.into()))
+            .timestamp(OffsetDateTime::UNIX_EPOCH)
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(event.as_request()).unwrap(),
+            serde_json::json!({
+                "idempotency_key": "key",
+                "external_customer_id": "ext",
+                "event_name": "api_call",
+                "properties": {"count": 1},
+                "timestamp": "1970-01-01T00:00:00Z",
+            })
+        );
+    }
+
+    #[test]
+    fn idempotency_keys_are_derived_from_content() {
+        let namespace = IdempotencyKeyNamespace::new("warehouse");
+        let build = |count: i32, offset_hours: i8| {
+            let offset = time::UtcOffset::from_hms(offset_hours, 0, 0).unwrap();
+            OwnedIngestEventRequest::builder()
+                .idempotency_key("ignored")
+                .customer_id(CustomerId::External("ext"))
+                .event_name("api_call")
+                .property("count", EventPropertyValue::Number(count.into()))
+                .property("region", EventPropertyValue::String("us-east-1".into()))
+                .timestamp(OffsetDateTime::UNIX_EPOCH.to_offset(offset))
+                .derive_idempotency_key(namespace)
+                .build()
+                .unwrap()
+                .idempotency_key
+        };
+        let key = build(1, 0);
+        // The derivation must never change, or replayed events would be
+        // ingested twice.
+        assert_eq!(key, "23110080-d12c-54a2-b0ac-3f4227f0e89d");
+        assert_eq!(build(1, 2), key);
+        assert_ne!(build(2, 0), key);
+
+        let event = OwnedIngestEventRequest::builder()
+            .customer_id(CustomerId::External("ext"))
+            .event_name("api_call")
+            .property("region", EventPropertyValue::String("us-east-1".into()))
+            .property("count", EventPropertyValue::Number(1.into()))
+            .timestamp(OffsetDateTime::UNIX_EPOCH)
+            .idempotency_key("other")
+            .build()
+            .unwrap();
+        assert_eq!(namespace.derive_key(&event.as_request()), key);
+        assert_ne!(
+            IdempotencyKeyNamespace::new("other").derive_key(&event.as_request()),
+            key
+        );
+    }
+
+    #[tokio::test]
+    async fn ingest_reports_per_event_outcomes() {
+        let transport = MockTransport::default();
+        transport.respond(
+            200,
+            serde_json::json!({
+                "validation_failed": [
+                    { "idempotency_key": "c", "validation_errors": ["bad timestamp"] },
+                ],
+                "debug": { "duplicate": ["b"], "ingested": ["a"] },
+            }),
+        );
+        let client = transport.client();
+        let events: Vec<_> = ["a", "b", "c"]
+            .into_iter()
+            .map(|key| {
+                OwnedIngestEventRequest::builder()
+                    .idempotency_key(key)
+                    .customer_id(CustomerId::Orb("cust"))
+                    .event_name("api_call")
+                    .timestamp(OffsetDateTime::UNIX_EPOCH)
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        let requests: Vec<_> = events.iter().map(|e| e.as_request()).collect();
+        let outcomes = client
+            .ingest_events_with_outcomes(None, &requests)
+            .await
+            .unwrap();
+        assert_eq!(
+            outcomes,
+            [
+                IngestEventOutcome::Ingested,
+                IngestEventOutcome::Duplicate,
+                IngestEventOutcome::Rejected {
+                    validation_errors: vec!["bad timestamp".into()],
+                },
+            ]
+        );
+        assert_eq!(transport.requests()[0].url.query(), Some("debug=true"));
+    }
+
+    #[test]
+    fn validation_reports_every_issue() {
+        let now = OffsetDateTime::now_utc();
+        let properties = BTreeMap::from([
+            ("a".repeat(300), EventPropertyValue::Bool(true)),
+            ("b".into(), EventPropertyValue::Bool(false)),
+        ]);
+        let event = IngestEventRequest {
+            idempotency_key: "",
+            customer_id: CustomerId::Orb("cust"),
+            event_name: "",
+            properties: &properties,
+            timestamp: now - Duration::from_secs(24 * 60 * 60),
+        };
+        let rules = EventValidationRules::DEFAULT.max_properties(1);
+        let err = event.validate_at(&rules, now).unwrap_err();
+        assert_eq!(
+            err.issues,
+            [
+                EventValidationIssue::EmptyIdempotencyKey,
+                EventValidationIssue::EmptyEventName,
+                EventValidationIssue::TooManyProperties { count: 2, max: 1 },
+                EventValidationIssue::PropertyKeyTooLong {
+                    key: "a".repeat(300),
+                    max: 256,
+                },
+                EventValidationIssue::TimestampTooOld {
+                    timestamp: event.timestamp,
+                },
+            ]
+        );
+
+        let no_properties = BTreeMap::new();
+        let event = IngestEventRequest {
+            idempotency_key: "key",
+            event_name: "api_call",
+            properties: &no_properties,
+            timestamp: now + Duration::from_secs(60),
+            ..event
+        };
+        let rules = EventValidationRules::DEFAULT;
+        assert!(event.validate_at(&rules, now).is_ok());
+        let amendment = AmendEventRequest {
+            customer_id: CustomerId::External(""),
+            event_name: "api_call",
+            properties: &properties,
+            timestamp: now + Duration::from_secs(3600),
+        };
+        let err = amendment.validate().unwrap_err();
+        assert!(err.issues.contains(&EventValidationIssue::EmptyCustomerId));
+        assert!(matches!(
+            err.issues.last(),
+            Some(EventValidationIssue::TimestampInFuture { .. })
+        ));
+
+        let old_amendment = AmendEventRequest {
+            customer_id: CustomerId::External("ext"),
+            properties: &no_properties,
+            timestamp: now - Duration::from_secs(30 * 24 * 60 * 60),
+            ..amendment
+        };
+        assert!(old_amendment.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn ingest_rejects_invalid_events_locally() {
+        let transport = MockTransport::default();
+        transport.respond(200, serde_json::json!({}));
+        let client = transport
+            .client_with(|builder| builder.event_validation(EventValidationRules::DEFAULT));
+        let event = |key| OwnedIngestEventRequest {
+            timestamp: OffsetDateTime::now_utc(),
+            ..test_event(key)
+        };
+        let valid = event("a");
+        let invalid = OwnedIngestEventRequest {
+            event_name: String::new(),
+            ..event("b")
+        };
+        let outcomes = client
+            .ingest_events_with_outcomes(None, &[valid.as_request(), invalid.as_request()])
+            .await
+            .unwrap();
+        assert_eq!(
+            outcomes,
+            [
+                IngestEventOutcome::Ingested,
+                IngestEventOutcome::RejectedLocally(EventValidationError {
+                    issues: vec![EventValidationIssue::EmptyEventName],
+                }),
+            ]
+        );
+        let body: serde_json::Value =
+            serde_json::from_slice(transport.requests()[0].body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["events"].as_array().unwrap().len(), 1);
+
+        // A request with no valid events is not sent.
+        let res = client
+            .ingest_events(IngestionMode::Production, None, &[invalid.as_request()])
+            .await
+            .unwrap();
+        assert!(res.validation_failed.is_empty());
+        assert_eq!(res.locally_rejected.len(), 1);
+        assert_eq!(res.locally_rejected[0].0, "b");
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn search_range_splits_timeframe_into_windows() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let start = OffsetDateTime::UNIX_EPOCH;
+        let config = EventSearchRangeConfig::DEFAULT.max_window(10 * day);
+        let windows = config.windows(Some(start), Some(start + 25 * day));
+        assert_eq!(
+            windows,
+            [
+                (Some(start), Some(start + 10 * day)),
+                (Some(start + 10 * day), Some(start + 20 * day)),
+                (Some(start + 20 * day), Some(start + 25 * day)),
+            ]
+        );
+        assert_eq!(config.windows(Some(start), None), [(Some(start), None)]);
+    }
+
+    #[tokio::test]
+    async fn search_range_splits_open_ended_timeframes() {
+        let page = serde_json::json!({
+            "data": [],
+            "pagination_metadata": { "next_cursor": null },
+        });
+        let transport = MockTransport::default();
+        for _ in 0..3 {
+            transport.respond(200, page.clone());
+        }
+        let client = transport.client();
+
+        let day = Duration::from_secs(24 * 60 * 60);
+        let params =
+            EventSearchParams::DEFAULT.timeframe_start(OffsetDateTime::now_utc() - 25 * day);
+        let config = EventSearchRangeConfig::DEFAULT.max_window(10 * day);
+        let events: Vec<Event> = client
+            .search_events_range(&params, &config)
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        for req in requests {
+            let body: serde_json::Value =
+                serde_json::from_slice(req.body.as_deref().unwrap()).unwrap();
+            assert!(body["timeframe_end"].is_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn search_range_merges_windows_in_timestamp_order() {
+        let event = |id: &str, secs: i64| {
+            serde_json::json!({
+                "id": id,
+                "customer_id": "cust",
+                "external_customer_id": null,
+                "event_name": "api_call",
+                "properties": {},
+                "timestamp": OffsetDateTime::from_unix_timestamp(secs)
+                    .unwrap()
+                    .format(&time::format_description::well_known::Rfc3339)
+                    .unwrap(),
+            })
+        };
+        let page = |events: Vec<serde_json::Value>| {
+            serde_json::json!({
+                "data": events,
+                "pagination_metadata": { "next_cursor": null },
+            })
+        };
+        let transport = MockTransport::default();
+        transport.respond(200, page(vec![event("b", 5), event("a", 1)]));
+        transport.respond(200, page(vec![event("c", 3)]));
+        transport.respond(200, page(vec![event("d", 20)]));
+        transport.respond(200, page(vec![]));
+        let client = transport.client();
+
+        let start = OffsetDateTime::UNIX_EPOCH;
+        let params = EventSearchParams::DEFAULT
+            .event_ids(&["a", "b", "c", "d"])
+            .timeframe_start(start)
+            .timeframe_end(start + Duration::from_secs(30));
+        let config = EventSearchRangeConfig::DEFAULT
+            .max_window(Duration::from_secs(15))
+            .max_event_ids(3)
+            .concurrency(1);
+        let events: Vec<Event> = client
+            .search_events_range(&params, &config)
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<_> = events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["a", "c", "b", "d"]);
+
+        let bodies: Vec<serde_json::Value> = transport
+            .requests()
+            .iter()
+            .map(|req| serde_json::from_slice(req.body.as_deref().unwrap()).unwrap())
+            .collect();
+        assert_eq!(
+            bodies,
+            [
+                serde_json::json!({
+                    "event_ids": ["a", "b", "c"],
+                    "timeframe_start": "1970-01-01T00:00:00Z",
+                    "timeframe_end": "1970-01-01T00:00:15Z",
+                }),
+                serde_json::json!({
+                    "event_ids": ["d"],
+                    "timeframe_start": "1970-01-01T00:00:00Z",
+                    "timeframe_end": "1970-01-01T00:00:15Z",
+                }),
+                serde_json::json!({
+                    "event_ids": ["a", "b", "c"],
+                    "timeframe_start": "1970-01-01T00:00:15Z",
+                    "timeframe_end": "1970-01-01T00:00:30Z",
+                }),
+                serde_json::json!({
+                    "event_ids": ["d"],
+                    "timeframe_start": "1970-01-01T00:00:15Z",
+                    "timeframe_end": "1970-01-01T00:00:30Z",
+                }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn bulk_amendments_are_retried_and_reported_in_order() {
+        let transport = MockTransport::default();
+        transport.respond(200, serde_json::json!({ "amended": "a" }));
+        transport.respond(503, serde_json::json!({ "title": "unavailable" }));
+        transport.respond(200, serde_json::json!({ "amended": "b" }));
+        transport.respond(400, serde_json::json!({ "title": "bad request" }));
+        let client = transport.client();
+
+        let amendment = |id: &str| {
+            let event = OwnedAmendEventRequest {
+                customer_id: OwnedCustomerId::Orb("cust".into()),
+                event_name: "api_call".into(),
+                properties: BTreeMap::new(),
+                timestamp: OffsetDateTime::UNIX_EPOCH,
+            };
+            (id.to_string(), event)
+        };
+        let config = BulkEventConfig::DEFAULT.concurrency(1);
+        let amendments = ["a", "b", "c"].map(amendment);
+        let amendments = futures_util::stream::iter(amendments);
+        let report = client.amend_events(amendments, &config).await;
+
+        let ids: Vec<_> = report.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert_eq!(report[0].result.as_ref().unwrap().amended, "a");
+        assert_eq!(report[1].result.as_ref().unwrap().amended, "b");
+        assert!(report[2].result.is_err());
+        let paths: Vec<_> = transport
+            .requests()
+            .iter()
+            .map(|req| req.url.path().to_string())
+            .collect();
+        // The amendment of "b" is retried after Orb reports it's unavailable.
+        let expected = ["a", "b", "b", "c"].map(|id| format!("/v1/events/{id}"));
+        assert_eq!(paths, expected);
+        // Both attempts to amend "b" carry the same idempotency key.
+        let requests = transport.requests();
+        let key = &requests[1].headers["idempotency-key"];
+        assert_eq!(&requests[2].headers["idempotency-key"], key);
+        assert_ne!(&requests[0].headers["idempotency-key"], key);
+    }
+
+    #[tokio::test]
+    async fn bulk_deprecations_report_each_event() {
+        let transport = MockTransport::default();
+        transport.respond(200, serde_json::json!({ "deprecated": "a" }));
+        transport.respond(404, serde_json::json!({ "title": "not found" }));
+        let client = transport.client();
+
+        let ids = futures_util::stream::iter(["a".to_string(), "b".to_string()]);
+        let report = client
+            .deprecate_events(ids, &BulkEventConfig::DEFAULT.concurrency(1))
+            .await;
+        assert_eq!(report[0].result.as_ref().unwrap().deprecated, "a");
+        assert!(report[1].result.as_ref().unwrap_err().is_not_found());
+        let path = transport.requests()[1].url.path().to_string();
+        assert_eq!(path, "/v1/events/b/deprecate");
+    }
+
+    #[tokio::test]
+    async fn event_volume_is_paginated() {
+        let bucket = |hour: i64, count: u64| {
+            serde_json::json!({
+                "count": count,
+                "timeframe_start": format!("1970-01-01T{hour:02}:00:00Z"),
+                "timeframe_end": format!("1970-01-01T{:02}:00:00Z", hour + 1),
+            })
+        };
+        let transport = MockTransport::default();
+        transport.respond(
+            200,
+            serde_json::json!({
+                "data": [bucket(0, 5)],
+                "pagination_metadata": { "next_cursor": "next" },
+            }),
+        );
+        transport.respond(
+            200,
+            serde_json::json!({
+                "data": [bucket(1, 0)],
+                "pagination_metadata": { "next_cursor": null },
+            }),
+        );
+        let client = transport.client();
+
+        let offset = UtcOffset::from_hms(2, 0, 0).unwrap();
+        let params = EventVolumeParams::DEFAULT
+            .timeframe_start(OffsetDateTime::UNIX_EPOCH.to_offset(offset))
+            .timeframe_end(OffsetDateTime::UNIX_EPOCH + Duration::from_secs(2 * 60 * 60));
+        let volume: Vec<EventVolume> = client
+            .get_event_volume(&params)
+            .try_collect()
+            .await
+            .unwrap();
+        let counts: Vec<_> = volume.iter().map(|v| v.count).collect();
+        assert_eq!(counts, [5, 0]);
+        assert_eq!(
+            volume[1].timeframe_start,
+            OffsetDateTime::UNIX_EPOCH + Duration::from_secs(60 * 60)
+        );
+
+        let req = &transport.requests()[1];
+        assert_eq!(req.url.path(), "/v1/events/volume");
+        assert_eq!(
+            req.url.query(),
+            Some(
+                "timeframe_start=1970-01-01T00%3A00%3A00Z\
+                 &timeframe_end=1970-01-01T02%3A00%3A00Z&limit=20&cursor=next"
+            )
         );
-        let _: serde_json::Value = self.send_request(req).await?;
-        Ok(())
     }
 }
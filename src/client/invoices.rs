@@ -22,10 +22,10 @@ use time::OffsetDateTime;
 
 use crate::client::customers::CustomerId;
 use crate::client::prices::Tier;
-use crate::client::Client;
+use crate::client::{Client, Page, RequestBuilder};
 use crate::config::ListParams;
 use crate::error::Error;
-use crate::util::StrIteratorExt;
+use crate::util::PathExt;
 use crate::Price;
 
 const INVOICES: [&str; 1] = ["invoices"];
@@ -300,7 +300,26 @@ impl<'a> InvoiceListParams<'a> {
     ///
     /// See [`ListParams::page_size`].
     pub const fn page_size(mut self, page_size: u64) -> Self {
-        self.inner = self.inner.page_size(page_size);
+        self.inner.page_size = page_size;
+        self
+    }
+
+    /// Sets the number of pages to fetch ahead of the consumer.
+    ///
+    /// See [`ListParams::prefetch`].
+    pub const fn prefetch(mut self, prefetch: usize) -> Self {
+        self.inner.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the cursor from which to start the list operation.
+    ///
+    /// See [`ListParams::cursor`].
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.inner = self.inner.cursor(cursor);
         self
     }
 
@@ -344,7 +363,24 @@ impl Client {
     pub fn list_invoices(
         &self,
         params: &InvoiceListParams,
-    ) -> impl Stream<Item = Result<Invoice, Error>> + '_ {
+    ) -> impl Stream<Item = Result<Invoice, Error>> + 'static {
+        let req = self.list_invoices_request(params);
+        self.stream_paginated_request(&params.inner, req)
+    }
+
+    /// Lists invoices as configured by `params`, one page at a time.
+    ///
+    /// Each page reports the cursor for the next page, which can be passed to
+    /// [`InvoiceListParams::cursor`] to resume the listing from that page.
+    pub fn list_invoices_pages(
+        &self,
+        params: &InvoiceListParams,
+    ) -> impl Stream<Item = Result<Page<Invoice>, Error>> + 'static {
+        let req = self.list_invoices_request(params);
+        self.stream_paginated_pages(&params.inner, req)
+    }
+
+    fn list_invoices_request(&self, params: &InvoiceListParams) -> RequestBuilder {
         let req = self.build_request(Method::GET, INVOICES);
         let req = match params.customer_filter {
             None => req,
@@ -374,19 +410,19 @@ impl Client {
                 req = req.query(&[("status[]", name)])
             }
         }
-        self.stream_paginated_request(&params.inner, req)
+        req
     }
 
     /// Gets an invoice by ID.
     pub async fn get_invoice(&self, id: &str) -> Result<Invoice, Error> {
-        let req = self.build_request(Method::GET, INVOICES.chain_one(id));
+        let req = self.build_request(Method::GET, INVOICES.chain_id(id));
         let res = self.send_request(req).await?;
         Ok(res)
     }
 
     /// Void an invoice by ID.
     pub async fn void_invoice(&self, id: &str) -> Result<Invoice, Error> {
-        let req = self.build_request(Method::POST, INVOICES.chain_one(id).chain_one("void"));
+        let req = self.build_request(Method::POST, INVOICES.chain_id(id).chain_one("void"));
         let res = self.send_request(req).await?;
         Ok(res)
     }
@@ -398,7 +434,7 @@ impl Client {
         params: &MarkInvoiceAsPaidParams<'_>,
     ) -> Result<Invoice, Error> {
         let req = self
-            .build_request(Method::POST, INVOICES.chain_one(id).chain_one("mark_paid"));
+            .build_request(Method::POST, INVOICES.chain_id(id).chain_one("mark_paid"));
         let req = req.json(params);
         let res = self.send_request(req).await?;
         Ok(res)
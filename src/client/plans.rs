@@ -21,10 +21,10 @@ use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
 use crate::Price;
-use crate::client::Client;
+use crate::client::{Client, Page, RequestBuilder};
 use crate::config::ListParams;
 use crate::error::Error;
-use crate::util::StrIteratorExt;
+use crate::util::PathExt;
 
 use super::coupons::Discount;
 
@@ -47,6 +47,48 @@ impl<'a> Default for PlanId<'a> {
     }
 }
 
+/// An owned [`PlanId`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum OwnedPlanId {
+    /// An Orb plan ID.
+    #[serde(rename = "plan_id")]
+    Orb(String),
+    /// An external plan ID.
+    #[serde(rename = "external_plan_id")]
+    External(String),
+}
+
+impl OwnedPlanId {
+    /// Borrows this ID as a [`PlanId`].
+    pub fn as_id(&self) -> PlanId<'_> {
+        match self {
+            OwnedPlanId::Orb(id) => PlanId::Orb(id),
+            OwnedPlanId::External(id) => PlanId::External(id),
+        }
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        match self {
+            OwnedPlanId::Orb(id) | OwnedPlanId::External(id) => id,
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedPlanId> for PlanId<'a> {
+    fn from(id: &'a OwnedPlanId) -> PlanId<'a> {
+        id.as_id()
+    }
+}
+
+impl From<PlanId<'_>> for OwnedPlanId {
+    fn from(id: PlanId<'_>) -> OwnedPlanId {
+        match id {
+            PlanId::Orb(id) => OwnedPlanId::Orb(id.into()),
+            PlanId::External(id) => OwnedPlanId::External(id.into()),
+        }
+    }
+}
+
 /// One phase of a plan.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct PlanPhase {
@@ -124,7 +166,26 @@ impl<'a> PlanListParams<'a> {
     ///
     /// See [`ListParams::page_size`].
     pub const fn page_size(mut self, page_size: u64) -> Self {
-        self.inner = self.inner.page_size(page_size);
+        self.inner.page_size = page_size;
+        self
+    }
+
+    /// Sets the number of pages to fetch ahead of the consumer.
+    ///
+    /// See [`ListParams::prefetch`].
+    pub const fn prefetch(mut self, prefetch: usize) -> Self {
+        self.inner.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the cursor from which to start the list operation.
+    ///
+    /// See [`ListParams::cursor`].
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.inner = self.inner.cursor(cursor);
         self
     }
 
@@ -140,18 +201,37 @@ impl Client {
     ///
     /// The underlying API call is paginated. The returned stream will fetch
     /// additional pages as it is consumed.
-    pub fn list_plans(&self, params: &PlanListParams) -> impl Stream<Item = Result<Plan, Error>> + '_ {
+    pub fn list_plans(
+        &self,
+        params: &PlanListParams,
+    ) -> impl Stream<Item = Result<Plan, Error>> + 'static {
+        let req = self.list_plans_request(params);
+        self.stream_paginated_request(&params.inner, req)
+    }
+
+    /// Lists all plans, one page at a time.
+    ///
+    /// Each page reports the cursor for the next page, which can be passed to
+    /// [`PlanListParams::cursor`] to resume the listing from that page.
+    pub fn list_plans_pages(
+        &self,
+        params: &PlanListParams,
+    ) -> impl Stream<Item = Result<Page<Plan>, Error>> + 'static {
+        let req = self.list_plans_request(params);
+        self.stream_paginated_pages(&params.inner, req)
+    }
+
+    fn list_plans_request(&self, params: &PlanListParams) -> RequestBuilder {
         let req = self.build_request(Method::GET, PLANS_PATH);
-        let req = match params.status_filter {
+        match params.status_filter {
             None => req,
             Some(status) => req.query(&[("status", status)]),
-        };
-        self.stream_paginated_request(&params.inner, req)
+        }
     }
 
     /// Gets a plan by ID.
     pub async fn get_plan(&self, id: &str) -> Result<Plan, Error> {
-        let req = self.build_request(Method::GET, PLANS_PATH.chain_one(id));
+        let req = self.build_request(Method::GET, PLANS_PATH.chain_id(id));
         let res = self.send_request(req).await?;
         Ok(res)
     }
@@ -162,7 +242,7 @@ impl Client {
             Method::GET,
             PLANS_PATH
                 .chain_one("external_plan_id")
-                .chain_one(external_id),
+                .chain_id(external_id),
         );
         let res = self.send_request(req).await?;
         Ok(res)
@@ -4,7 +4,9 @@ use time::OffsetDateTime;
 /// Orb credit allocation details on a price.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct CreditAllocation {
+    /// The currency of the allocated credits.
     pub currency: String,
+    /// Whether unused credits roll over into the next period.
     #[serde(default)]
     pub allows_rollover: bool,
 }
@@ -31,13 +31,13 @@ use crate::{
     RedeemedCoupon,
     SubscriptionAdjustmentInterval
 };
-use crate::client::customers::{Customer, CustomerId, CustomerResponse};
+use crate::client::customers::{Customer, CustomerId, CustomerResponse, OwnedCustomerId};
 use crate::client::marketplaces::ExternalMarketplace;
-use crate::client::plans::{Plan, PlanId};
-use crate::client::Client;
+use crate::client::plans::{OwnedPlanId, Plan, PlanId};
+use crate::client::{Client, Page, RequestBuilder};
 use crate::config::ListParams;
 use crate::error::Error;
-use crate::util::StrIteratorExt;
+use crate::util::{require_field, require_some, PathExt};
 
 use super::prices::PriceInterval;
 
@@ -117,6 +117,233 @@ pub struct SubscriptionExternalMarketplaceRequest<'a> {
     pub reporting_id: &'a str,
 }
 
+/// An owned [`CreateSubscriptionRequest`].
+///
+/// Unlike [`CreateSubscriptionRequest`], an owned request does not borrow its
+/// fields, so it can be built in one place and sent later, e.g., from another
+/// task. Construct one with [`OwnedCreateSubscriptionRequest::builder`], and
+/// pass it to [`Client::create_subscription`] via [`as_request`].
+///
+/// [`as_request`]: OwnedCreateSubscriptionRequest::as_request
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedCreateSubscriptionRequest {
+    /// The customer to subscribe.
+    pub customer_id: OwnedCustomerId,
+    /// The plan that the customer should be subscribed to.
+    pub plan_id: OwnedPlanId,
+    /// The date at which Orb should start billing for the subscription,
+    /// localized ot the customer's timezone.
+    pub start_date: Option<OffsetDateTime>,
+    /// The name of the external marketplace that the subscription is attached
+    /// to.
+    pub external_marketplace: Option<OwnedSubscriptionExternalMarketplaceRequest>,
+    /// Whether to align billing periods with the subscription's start date.
+    pub align_billing_with_subscription_start_date: Option<bool>,
+    /// The subscription's override minimum amount for the plan.
+    pub minimum_amount: Option<String>,
+    /// The subscription's override net terms for the plan.
+    pub net_terms: Option<i64>,
+    /// Determines whether issued invoices for this subscription will
+    /// automatically be charged with the saved payment method on the due date.
+    pub auto_collection: Option<bool>,
+    /// Determines the default memo on this subscription's invoices.
+    pub default_invoice_memo: Option<String>,
+    /// An idempotency key can ensure that if the same request comes in
+    /// multiple times in a 48-hour period, only one makes changes.
+    pub idempotency_key: Option<String>,
+    /// Optionally provide a list of overrides for prices on the plan
+    pub price_overrides: Option<Vec<QuantityOnlyPriceOverride>>,
+    /// Coupon to apply to this subscription
+    pub coupon_redemption_code: Option<String>,
+    /// When this subscription's accrued usage reaches this threshold, an invoice
+    /// will be issued for the subscription.
+    pub invoicing_threshold: Option<String>,
+}
+
+impl OwnedCreateSubscriptionRequest {
+    /// Returns a builder for a create subscription request.
+    pub fn builder() -> CreateSubscriptionRequestBuilder {
+        CreateSubscriptionRequestBuilder::default()
+    }
+
+    /// Borrows this request as a [`CreateSubscriptionRequest`].
+    pub fn as_request(&self) -> CreateSubscriptionRequest<'_> {
+        CreateSubscriptionRequest {
+            customer_id: self.customer_id.as_id(),
+            plan_id: self.plan_id.as_id(),
+            start_date: self.start_date,
+            external_marketplace: self.external_marketplace.as_ref().map(|marketplace| {
+                SubscriptionExternalMarketplaceRequest {
+                    kind: marketplace.kind.clone(),
+                    reporting_id: &marketplace.reporting_id,
+                }
+            }),
+            align_billing_with_subscription_start_date: self
+                .align_billing_with_subscription_start_date,
+            minimum_amount: self.minimum_amount.as_deref(),
+            net_terms: self.net_terms,
+            auto_collection: self.auto_collection,
+            default_invoice_memo: self.default_invoice_memo.as_deref(),
+            idempotency_key: self.idempotency_key.as_deref(),
+            price_overrides: self.price_overrides.clone(),
+            coupon_redemption_code: self.coupon_redemption_code.as_deref(),
+            invoicing_threshold: self.invoicing_threshold.as_deref(),
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedCreateSubscriptionRequest> for CreateSubscriptionRequest<'a> {
+    fn from(request: &'a OwnedCreateSubscriptionRequest) -> CreateSubscriptionRequest<'a> {
+        request.as_request()
+    }
+}
+
+/// An external marketplace for a subscription, in owned form.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedSubscriptionExternalMarketplaceRequest {
+    /// The kind of the external marketplace.
+    pub kind: ExternalMarketplace,
+    /// The ID of the subscription in the external marketplace.
+    pub reporting_id: String,
+}
+
+/// A builder for an [`OwnedCreateSubscriptionRequest`].
+///
+/// The `customer_id` and `plan_id` fields are required.
+#[derive(Debug, Default, Clone)]
+pub struct CreateSubscriptionRequestBuilder {
+    customer_id: Option<OwnedCustomerId>,
+    plan_id: Option<OwnedPlanId>,
+    start_date: Option<OffsetDateTime>,
+    external_marketplace: Option<OwnedSubscriptionExternalMarketplaceRequest>,
+    align_billing_with_subscription_start_date: Option<bool>,
+    minimum_amount: Option<String>,
+    net_terms: Option<i64>,
+    auto_collection: Option<bool>,
+    default_invoice_memo: Option<String>,
+    idempotency_key: Option<String>,
+    price_overrides: Option<Vec<QuantityOnlyPriceOverride>>,
+    coupon_redemption_code: Option<String>,
+    invoicing_threshold: Option<String>,
+}
+
+impl CreateSubscriptionRequestBuilder {
+    /// Sets the customer to subscribe.
+    pub fn customer_id<I: Into<OwnedCustomerId>>(mut self, customer_id: I) -> Self {
+        self.customer_id = Some(customer_id.into());
+        self
+    }
+
+    /// Sets the plan that the customer should be subscribed to.
+    pub fn plan_id<I: Into<OwnedPlanId>>(mut self, plan_id: I) -> Self {
+        self.plan_id = Some(plan_id.into());
+        self
+    }
+
+    /// Sets the date at which Orb should start billing for the subscription.
+    pub fn start_date(mut self, start_date: OffsetDateTime) -> Self {
+        self.start_date = Some(start_date);
+        self
+    }
+
+    /// Sets the external marketplace that the subscription is attached to.
+    pub fn external_marketplace<S: Into<String>>(
+        mut self,
+        kind: ExternalMarketplace,
+        reporting_id: S,
+    ) -> Self {
+        self.external_marketplace = Some(OwnedSubscriptionExternalMarketplaceRequest {
+            kind,
+            reporting_id: reporting_id.into(),
+        });
+        self
+    }
+
+    /// Sets whether to align billing periods with the subscription's start
+    /// date.
+    pub fn align_billing_with_subscription_start_date(mut self, align: bool) -> Self {
+        self.align_billing_with_subscription_start_date = Some(align);
+        self
+    }
+
+    /// Sets the subscription's override minimum amount for the plan.
+    pub fn minimum_amount<S: Into<String>>(mut self, minimum_amount: S) -> Self {
+        self.minimum_amount = Some(minimum_amount.into());
+        self
+    }
+
+    /// Sets the subscription's override net terms for the plan.
+    pub fn net_terms(mut self, net_terms: i64) -> Self {
+        self.net_terms = Some(net_terms);
+        self
+    }
+
+    /// Sets whether issued invoices will automatically be charged with the
+    /// saved payment method on the due date.
+    pub fn auto_collection(mut self, auto_collection: bool) -> Self {
+        self.auto_collection = Some(auto_collection);
+        self
+    }
+
+    /// Sets the default memo on the subscription's invoices.
+    pub fn default_invoice_memo<S: Into<String>>(mut self, memo: S) -> Self {
+        self.default_invoice_memo = Some(memo.into());
+        self
+    }
+
+    /// Sets the idempotency key for the request.
+    pub fn idempotency_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Adds an override for a price on the plan.
+    pub fn price_override(mut self, price_override: QuantityOnlyPriceOverride) -> Self {
+        self.price_overrides
+            .get_or_insert_with(Vec::new)
+            .push(price_override);
+        self
+    }
+
+    /// Sets the coupon to apply to the subscription.
+    pub fn coupon_redemption_code<S: Into<String>>(mut self, code: S) -> Self {
+        self.coupon_redemption_code = Some(code.into());
+        self
+    }
+
+    /// Sets the accrued usage threshold at which an invoice will be issued.
+    pub fn invoicing_threshold<S: Into<String>>(mut self, threshold: S) -> Self {
+        self.invoicing_threshold = Some(threshold.into());
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// Returns [`Error::InvalidRequest`] if a required field is missing.
+    pub fn build(self) -> Result<OwnedCreateSubscriptionRequest, Error> {
+        let customer_id = require_some(self.customer_id, "customer_id")?;
+        require_field(customer_id.id(), "customer_id")?;
+        let plan_id = require_some(self.plan_id, "plan_id")?;
+        require_field(plan_id.id(), "plan_id")?;
+        Ok(OwnedCreateSubscriptionRequest {
+            customer_id,
+            plan_id,
+            start_date: self.start_date,
+            external_marketplace: self.external_marketplace,
+            align_billing_with_subscription_start_date: self
+                .align_billing_with_subscription_start_date,
+            minimum_amount: self.minimum_amount,
+            net_terms: self.net_terms,
+            auto_collection: self.auto_collection,
+            default_invoice_memo: self.default_invoice_memo,
+            idempotency_key: self.idempotency_key,
+            price_overrides: self.price_overrides,
+            coupon_redemption_code: self.coupon_redemption_code,
+            invoicing_threshold: self.invoicing_threshold,
+        })
+    }
+}
+
 /// Updates the quantity for a fixed fee
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct UpdatePriceQuantityRequest<'a> {
@@ -167,6 +394,151 @@ pub struct SchedulePlanChangeRequest<'a> {
     pub billing_cycle_alignment: Option<BillingCycleAlignment>,
 }
 
+/// An owned [`SchedulePlanChangeRequest`].
+///
+/// Construct one with [`OwnedSchedulePlanChangeRequest::builder`], and pass it
+/// to [`Client::schedule_plan_change`] via [`as_request`].
+///
+/// [`as_request`]: OwnedSchedulePlanChangeRequest::as_request
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedSchedulePlanChangeRequest {
+    /// The plan to switch to.
+    pub plan_id: OwnedPlanId,
+    /// When the plan change should take place.
+    pub change_option: ChangeOption,
+    /// The date that the plan change should take effect. This parameter
+    /// can only be passed if the change_option is requested_date.
+    pub change_date: Option<String>,
+    /// Optionally provide a list of overrides for prices on the plan
+    pub price_overrides: Option<Vec<QuantityOnlyPriceOverride>>,
+    /// Coupon to apply to this subscription
+    pub coupon_redemption_code: Option<String>,
+    /// When this subscription's accrued usage reaches this threshold, an invoice
+    /// will be issued for the subscription.
+    pub invoicing_threshold: Option<String>,
+    /// Reset billing periods to be aligned with the plan change's effective date
+    /// or start of the month.
+    pub billing_cycle_alignment: Option<BillingCycleAlignment>,
+}
+
+impl OwnedSchedulePlanChangeRequest {
+    /// Returns a builder for a schedule plan change request.
+    pub fn builder() -> SchedulePlanChangeRequestBuilder {
+        SchedulePlanChangeRequestBuilder::default()
+    }
+
+    /// Borrows this request as a [`SchedulePlanChangeRequest`].
+    pub fn as_request(&self) -> SchedulePlanChangeRequest<'_> {
+        SchedulePlanChangeRequest {
+            plan_id: self.plan_id.as_id(),
+            change_option: self.change_option.clone(),
+            change_date: self.change_date.as_deref(),
+            price_overrides: self.price_overrides.clone(),
+            coupon_redemption_code: self.coupon_redemption_code.as_deref(),
+            invoicing_threshold: self.invoicing_threshold.as_deref(),
+            billing_cycle_alignment: self.billing_cycle_alignment.clone(),
+        }
+    }
+}
+
+impl<'a> From<&'a OwnedSchedulePlanChangeRequest> for SchedulePlanChangeRequest<'a> {
+    fn from(request: &'a OwnedSchedulePlanChangeRequest) -> SchedulePlanChangeRequest<'a> {
+        request.as_request()
+    }
+}
+
+/// A builder for an [`OwnedSchedulePlanChangeRequest`].
+///
+/// The `plan_id` field is required, as is the `change_date` field when the
+/// change option is [`ChangeOption::RequestedDate`].
+#[derive(Debug, Default, Clone)]
+pub struct SchedulePlanChangeRequestBuilder {
+    plan_id: Option<OwnedPlanId>,
+    change_option: ChangeOption,
+    change_date: Option<String>,
+    price_overrides: Option<Vec<QuantityOnlyPriceOverride>>,
+    coupon_redemption_code: Option<String>,
+    invoicing_threshold: Option<String>,
+    billing_cycle_alignment: Option<BillingCycleAlignment>,
+}
+
+impl SchedulePlanChangeRequestBuilder {
+    /// Sets the plan to switch to.
+    pub fn plan_id<I: Into<OwnedPlanId>>(mut self, plan_id: I) -> Self {
+        self.plan_id = Some(plan_id.into());
+        self
+    }
+
+    /// Sets when the plan change should take place.
+    pub fn change_option(mut self, change_option: ChangeOption) -> Self {
+        self.change_option = change_option;
+        self
+    }
+
+    /// Sets the date that the plan change should take effect.
+    ///
+    /// Only valid with [`ChangeOption::RequestedDate`].
+    pub fn change_date<S: Into<String>>(mut self, change_date: S) -> Self {
+        self.change_date = Some(change_date.into());
+        self
+    }
+
+    /// Adds an override for a price on the plan.
+    pub fn price_override(mut self, price_override: QuantityOnlyPriceOverride) -> Self {
+        self.price_overrides
+            .get_or_insert_with(Vec::new)
+            .push(price_override);
+        self
+    }
+
+    /// Sets the coupon to apply to the subscription.
+    pub fn coupon_redemption_code<S: Into<String>>(mut self, code: S) -> Self {
+        self.coupon_redemption_code = Some(code.into());
+        self
+    }
+
+    /// Sets the accrued usage threshold at which an invoice will be issued.
+    pub fn invoicing_threshold<S: Into<String>>(mut self, threshold: S) -> Self {
+        self.invoicing_threshold = Some(threshold.into());
+        self
+    }
+
+    /// Sets how billing periods should be aligned after the plan change.
+    pub fn billing_cycle_alignment(mut self, alignment: BillingCycleAlignment) -> Self {
+        self.billing_cycle_alignment = Some(alignment);
+        self
+    }
+
+    /// Builds the request.
+    ///
+    /// Returns [`Error::InvalidRequest`] if a required field is missing or if
+    /// a change date is given for a change option other than
+    /// [`ChangeOption::RequestedDate`].
+    pub fn build(self) -> Result<OwnedSchedulePlanChangeRequest, Error> {
+        let plan_id = require_some(self.plan_id, "plan_id")?;
+        require_field(plan_id.id(), "plan_id")?;
+        match (&self.change_option, &self.change_date) {
+            (ChangeOption::RequestedDate, None) => Err(Error::InvalidRequest {
+                detail: "change_date is required when change_option is requested_date".into(),
+            }),
+            (ChangeOption::RequestedDate, Some(_)) | (_, None) => {
+                Ok(OwnedSchedulePlanChangeRequest {
+                    plan_id,
+                    change_option: self.change_option,
+                    change_date: self.change_date,
+                    price_overrides: self.price_overrides,
+                    coupon_redemption_code: self.coupon_redemption_code,
+                    invoicing_threshold: self.invoicing_threshold,
+                    billing_cycle_alignment: self.billing_cycle_alignment,
+                })
+            }
+            (_, Some(_)) => Err(Error::InvalidRequest {
+                detail: "change_date is only allowed when change_option is requested_date".into(),
+            }),
+        }
+    }
+}
+
 /// Options for when a plan transition should take place.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize_enum_str, Serialize_enum_str)]
 #[serde(rename_all = "snake_case")]
@@ -436,7 +808,26 @@ impl<'a> SubscriptionListParams<'a> {
     ///
     /// See [`ListParams::page_size`].
     pub const fn page_size(mut self, page_size: u64) -> Self {
-        self.inner = self.inner.page_size(page_size);
+        self.inner.page_size = page_size;
+        self
+    }
+
+    /// Sets the number of pages to fetch ahead of the consumer.
+    ///
+    /// See [`ListParams::prefetch`].
+    pub const fn prefetch(mut self, prefetch: usize) -> Self {
+        self.inner.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the cursor from which to start the list operation.
+    ///
+    /// See [`ListParams::cursor`].
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.inner = self.inner.cursor(cursor);
         self
     }
 
@@ -453,6 +844,35 @@ impl<'a> SubscriptionListParams<'a> {
     }
 }
 
+impl Subscription<CustomerResponse> {
+    /// Returns the subscription, or `None` if its customer has been deleted.
+    fn into_subscription(self) -> Result<Option<Subscription>, Error> {
+        let Some(customer) = self.customer.into_customer()? else {
+            return Ok(None);
+        };
+        Ok(Some(Subscription {
+            id: self.id,
+            customer,
+            plan: self.plan,
+            start_date: self.start_date,
+            end_date: self.end_date,
+            status: self.status,
+            current_billing_period_start_date: self.current_billing_period_start_date,
+            current_billing_period_end_date: self.current_billing_period_end_date,
+            active_plan_phase_order: self.active_plan_phase_order,
+            fixed_fee_quantity_schedule: self.fixed_fee_quantity_schedule,
+            net_terms: self.net_terms,
+            auto_collection: self.auto_collection,
+            default_invoice_memo: self.default_invoice_memo,
+            created_at: self.created_at,
+            redeemed_coupon: self.redeemed_coupon,
+            price_intervals: self.price_intervals,
+            adjustment_intervals: self.adjustment_intervals,
+            invoicing_threshold: self.invoicing_threshold,
+        }))
+    }
+}
+
 impl Client {
     /// Lists subscriptions as configured by `params`.
     ///
@@ -461,56 +881,38 @@ impl Client {
     pub fn list_subscriptions(
         &self,
         params: &SubscriptionListParams,
-    ) -> impl Stream<Item = Result<Subscription, Error>> + '_ {
+    ) -> impl Stream<Item = Result<Subscription, Error>> + 'static {
+        let req = self.list_subscriptions_request(params);
+        self.stream_paginated_request(&params.inner, req)
+            .try_filter_map(|subscription: Subscription<CustomerResponse>| async {
+                subscription.into_subscription()
+            })
+    }
+
+    /// Lists subscriptions as configured by `params`, one page at a time.
+    ///
+    /// Each page reports the cursor for the next page, which can be passed to
+    /// [`SubscriptionListParams::cursor`] to resume the listing from that page.
+    pub fn list_subscriptions_pages(
+        &self,
+        params: &SubscriptionListParams,
+    ) -> impl Stream<Item = Result<Page<Subscription>, Error>> + 'static {
+        let req = self.list_subscriptions_request(params);
+        self.stream_paginated_pages(&params.inner, req)
+            .and_then(|page| async { page.try_filter_map(Subscription::into_subscription) })
+    }
+
+    fn list_subscriptions_request(&self, params: &SubscriptionListParams) -> RequestBuilder {
         let req = self.build_request(Method::GET, SUBSCRIPTIONS_PATH);
         let req = match params.customer_id_filter {
             None => req,
             Some(CustomerId::Orb(id)) => req.query(&[("customer_id", id)]),
             Some(CustomerId::External(id)) => req.query(&[("external_customer_id", id)]),
         };
-        let req = match params.status_filter {
+        match params.status_filter {
             None => req,
             Some(status) => req.query(&[("status", status)]),
-        };
-        self.stream_paginated_request(&params.inner, req)
-            .try_filter_map(|subscription: Subscription<CustomerResponse>| async move {
-                match subscription.customer {
-                    CustomerResponse::Normal(customer) => Ok(Some(Subscription {
-                        id: subscription.id,
-                        customer,
-                        plan: subscription.plan,
-                        start_date: subscription.start_date,
-                        end_date: subscription.end_date,
-                        status: subscription.status,
-                        current_billing_period_start_date: subscription
-                            .current_billing_period_start_date,
-                        current_billing_period_end_date: subscription
-                            .current_billing_period_end_date,
-                        active_plan_phase_order: subscription.active_plan_phase_order,
-                        fixed_fee_quantity_schedule: subscription.fixed_fee_quantity_schedule,
-                        net_terms: subscription.net_terms,
-                        auto_collection: subscription.auto_collection,
-                        default_invoice_memo: subscription.default_invoice_memo,
-                        created_at: subscription.created_at,
-                        redeemed_coupon: subscription.redeemed_coupon,
-                        price_intervals: subscription.price_intervals,
-                        adjustment_intervals: subscription.adjustment_intervals,
-                        invoicing_threshold: subscription.invoicing_threshold,
-                    })),
-                    CustomerResponse::Deleted {
-                        id: _,
-                        deleted: true,
-                    } => Ok(None),
-                    CustomerResponse::Deleted { id, deleted: false } => {
-                        Err(Error::UnexpectedResponse {
-                            detail: format!(
-                                "customer {id} used deleted response shape \
-                                but deleted field was `false`"
-                            ),
-                        })
-                    }
-                }
-            })
+        }
     }
 
     /// Creates a new subscription.
@@ -530,7 +932,7 @@ impl Client {
 
     /// Gets a subscription by ID.
     pub async fn get_subscription(&self, id: &str) -> Result<Subscription, Error> {
-        let req = self.build_request(Method::GET, SUBSCRIPTIONS_PATH.chain_one(id));
+        let req = self.build_request(Method::GET, SUBSCRIPTIONS_PATH.chain_id(id));
         let res = self.send_request(req).await?;
         Ok(res)
     }
@@ -540,7 +942,7 @@ impl Client {
         let req = self.build_request(
             Method::POST,
             SUBSCRIPTIONS_PATH
-            .chain_one(id)
+            .chain_id(id)
             .chain_one("update_fixed_fee_quantity")
         );
         let req = req.json(params);
@@ -553,7 +955,7 @@ impl Client {
         let req = self.build_request(
             Method::POST,
             SUBSCRIPTIONS_PATH
-            .chain_one(id)
+            .chain_id(id)
             .chain_one("schedule_plan_change")
         );
         let req = req.json(params);
@@ -566,7 +968,7 @@ impl Client {
         let mut req = self.build_request(
             Method::POST,
             SUBSCRIPTIONS_PATH
-            .chain_one(id)
+            .chain_id(id)
             .chain_one("price_intervals")
         );
         if let Some(key) = params.idempotency_key {
@@ -583,7 +985,7 @@ impl Client {
         let req = self.build_request(
             Method::POST,
             SUBSCRIPTIONS_PATH
-            .chain_one(id)
+            .chain_id(id)
             .chain_one("cancel")
         );
         let req = req.json(params);
@@ -596,7 +998,7 @@ impl Client {
         let req = self.build_request(
             Method::POST,
             SUBSCRIPTIONS_PATH
-            .chain_one(id)
+            .chain_id(id)
             .chain_one("unschedule_cancellation")
         );
         let res = self.send_request(req).await?;
@@ -608,7 +1010,7 @@ impl Client {
         let req = self.build_request(
             Method::PUT,
             SUBSCRIPTIONS_PATH
-            .chain_one(id)
+            .chain_id(id)
         );
         let req = req.json(params);
         let res = self.send_request(req).await?;
@@ -620,7 +1022,7 @@ impl Client {
         let req = self.build_request(
             Method::GET,
             SUBSCRIPTIONS_PATH
-            .chain_one(id)
+            .chain_id(id)
             .chain_one("costs")
         );
         let req = req.json(params);
@@ -633,7 +1035,7 @@ impl Client {
         let req = self.build_request(
             Method::GET,
             SUBSCRIPTIONS_PATH
-            .chain_one(id)
+            .chain_id(id)
             .chain_one("usage")
         );
         let req = req.json(params);
@@ -641,3 +1043,60 @@ impl Client {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_plan_change_builder_validates_change_date() {
+        let err = OwnedSchedulePlanChangeRequest::builder()
+            .plan_id(PlanId::Orb("plan"))
+            .change_option(ChangeOption::Immediate)
+            .change_date("2024-01-01")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidRequest { .. }), "{err}");
+
+        let err = OwnedSchedulePlanChangeRequest::builder()
+            .plan_id(PlanId::Orb("plan"))
+            .change_option(ChangeOption::RequestedDate)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidRequest { .. }), "{err}");
+
+        let request = OwnedSchedulePlanChangeRequest::builder()
+            .plan_id(PlanId::External("ext"))
+            .change_option(ChangeOption::RequestedDate)
+            .change_date("2024-01-01")
+            .build()
+            .unwrap();
+        let json = serde_json::to_value(request.as_request()).unwrap();
+        assert_eq!(json["external_plan_id"], "ext");
+        assert_eq!(json["change_option"], "requested_date");
+        assert_eq!(json["change_date"], "2024-01-01");
+    }
+
+    #[test]
+    fn create_subscription_builder_requires_customer_and_plan() {
+        let err = OwnedCreateSubscriptionRequest::builder()
+            .customer_id(CustomerId::Orb("cust"))
+            .build()
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::InvalidRequest { detail } if detail == "plan_id is required"),
+            "{err}"
+        );
+
+        let request = OwnedCreateSubscriptionRequest::builder()
+            .customer_id(CustomerId::Orb("cust"))
+            .plan_id(PlanId::Orb("plan"))
+            .idempotency_key("key")
+            .build()
+            .unwrap();
+        let borrowed = request.as_request();
+        assert_eq!(borrowed.customer_id, CustomerId::Orb("cust"));
+        assert_eq!(borrowed.plan_id, PlanId::Orb("plan"));
+        assert_eq!(borrowed.idempotency_key, Some("key"));
+    }
+}
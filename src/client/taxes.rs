@@ -40,6 +40,27 @@ pub struct TaxId {
     pub country: String,
 }
 
+/// An owned [`TaxIdRequest`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OwnedTaxIdRequest {
+    /// The type of the tax ID.
+    pub type_: TaxIdType,
+    /// The value of the tax ID.
+    pub value: String,
+    /// The country of the tax ID.
+    pub country: String,
+}
+
+impl<'a> From<&'a OwnedTaxIdRequest> for TaxIdRequest<'a> {
+    fn from(tax_id: &'a OwnedTaxIdRequest) -> TaxIdRequest<'a> {
+        TaxIdRequest {
+            type_: tax_id.type_.clone(),
+            value: &tax_id.value,
+            country: tax_id.country.clone(),
+        }
+    }
+}
+
 /// The type of a [`TaxId`].
 ///
 /// See: <https://docs.withorb.com/docs/orb-docs/api-reference/schemas/customer-tax-id>
@@ -13,12 +13,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
 use std::time::Duration;
 
 use once_cell::sync::Lazy;
-use reqwest::Url;
+use rand::Rng;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Proxy, Url};
 
-use crate::Client;
+use crate::client::events::EventValidationRules;
+use crate::client::ClientInner;
+use crate::limit::Limiter;
+use crate::metrics::ClientMetrics;
+use crate::transport::{HttpTransport, ReqwestTransport};
+use crate::{Client, Error};
 
 pub static DEFAULT_ENDPOINT: Lazy<Url> = Lazy::new(|| {
     "https://api.billwithorb.com/v1"
@@ -35,29 +43,514 @@ pub struct ClientConfig {
 /// A builder for a [`Client`].
 pub struct ClientBuilder {
     endpoint: Url,
+    timeout: Duration,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    default_headers: HeaderMap,
+    proxies: Vec<Proxy>,
+    reqwest_client: Option<reqwest::Client>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    retry_policy: RetryPolicy,
+    limits: RequestLimits,
+    ingest_limits: Option<RequestLimits>,
+    metrics: Option<Arc<dyn ClientMetrics>>,
+    auto_idempotency_keys: bool,
+    event_validation: Option<EventValidationRules>,
 }
 
 impl Default for ClientBuilder {
     fn default() -> ClientBuilder {
         ClientBuilder {
             endpoint: DEFAULT_ENDPOINT.clone(),
+            timeout: Duration::from_secs(60),
+            connect_timeout: None,
+            read_timeout: None,
+            user_agent: None,
+            default_headers: HeaderMap::new(),
+            proxies: vec![],
+            reqwest_client: None,
+            transport: None,
+            retry_policy: RetryPolicy::DEFAULT,
+            limits: RequestLimits::UNLIMITED,
+            ingest_limits: None,
+            metrics: None,
+            auto_idempotency_keys: false,
+            event_validation: None,
         }
     }
 }
 
 impl ClientBuilder {
+    /// Sets the endpoint of the Orb API.
+    ///
+    /// The default endpoint is `https://api.billwithorb.com/v1`. Overriding
+    /// the endpoint is primarily useful for pointing the client at a local
+    /// stand-in for the Orb API in tests.
+    pub fn endpoint(mut self, endpoint: Url) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Sets the total timeout for each request attempt, from the time the
+    /// connection is established until the response body is received.
+    ///
+    /// The default timeout is 60 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the timeout for establishing a connection to the API.
+    ///
+    /// By default, only the total timeout applies.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for each read from the connection to the API.
+    ///
+    /// By default, only the total timeout applies.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header to send with each request.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets headers to send with each request.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Adds a proxy through which to send requests.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Sets a preconfigured [`reqwest::Client`] through which to send
+    /// requests.
+    ///
+    /// When set, the timeout, user agent, default header, and proxy settings
+    /// on this builder are ignored in favor of the settings on the provided
+    /// client.
+    pub fn reqwest_client(mut self, client: reqwest::Client) -> Self {
+        self.reqwest_client = Some(client);
+        self
+    }
+
+    /// Sets the transport through which to send requests.
+    ///
+    /// By default, requests are sent via a [`ReqwestTransport`] configured
+    /// by the other settings on this builder.
+    pub fn transport<T>(mut self, transport: T) -> Self
+    where
+        T: HttpTransport,
+    {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Sets the policy for automatically retrying failed requests.
+    ///
+    /// The default policy is [`RetryPolicy::DEFAULT`].
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Sets limits on the rate and concurrency of requests.
+    ///
+    /// The limits are shared by all requests made through the client,
+    /// including event ingestion requests unless separate limits are
+    /// configured via [`ClientBuilder::ingest_limits`].
+    ///
+    /// By default, requests are not limited.
+    pub fn limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets separate limits on the rate and concurrency of event ingestion
+    /// requests.
+    ///
+    /// When set, event ingestion requests draw from their own budget rather
+    /// than the budget configured via [`ClientBuilder::limits`], so that a
+    /// burst of ingestion cannot starve other requests, or vice versa.
+    pub fn ingest_limits(mut self, limits: RequestLimits) -> Self {
+        self.ingest_limits = Some(limits);
+        self
+    }
+
+    /// Sets an observer to notify of every request made by the client.
+    ///
+    /// Enable the `metrics` feature to use `MetricsRecorder`, which records
+    /// to the [`metrics`](https://docs.rs/metrics) crate.
+    pub fn metrics<M>(mut self, metrics: M) -> Self
+    where
+        M: ClientMetrics,
+    {
+        self.metrics = Some(Arc::new(metrics));
+        self
+    }
+
+    /// Sets whether to generate an idempotency key for every `POST` and `PUT`
+    /// request that does not already have one.
+    ///
+    /// Generated keys are random UUIDs. A request's key is reused across
+    /// automatic retries of that request, so that a request that times out
+    /// after Orb has processed it is not processed twice. Requests with an
+    /// idempotency key are retried according to the [`RetryPolicy`].
+    ///
+    /// Keys provided by a request type, like
+    /// [`CreateCustomerRequest::idempotency_key`], or via
+    /// [`RequestOptions::idempotency_key`] take precedence.
+    ///
+    /// The default is `false`.
+    ///
+    /// [`CreateCustomerRequest::idempotency_key`]: crate::CreateCustomerRequest::idempotency_key
+    pub fn auto_idempotency_keys(mut self, enabled: bool) -> Self {
+        self.auto_idempotency_keys = enabled;
+        self
+    }
+
+    /// Enables client-side validation of ingested events.
+    ///
+    /// When enabled, [`Client::ingest_events`] validates each event against
+    /// the specified rules before sending it. Invalid events are not sent,
+    /// and are reported in [`IngestEventResponse::validation_failed`], while
+    /// the valid events in the same request are ingested as usual.
+    ///
+    /// Validation is disabled by default.
+    ///
+    /// [`IngestEventResponse::validation_failed`]: crate::IngestEventResponse::validation_failed
+    pub fn event_validation(mut self, rules: EventValidationRules) -> Self {
+        self.event_validation = Some(rules);
+        self
+    }
+
     /// Creates a [`Client`] that incorporates the optional parameters
     /// configured on the builder and the specified required parameters.
-    pub fn build(self, config: ClientConfig) -> Client {
-        let inner = reqwest::ClientBuilder::new()
-            .redirect(reqwest::redirect::Policy::none())
-            .timeout(Duration::from_secs(60))
-            .build()
-            .unwrap();
-        Client {
-            inner,
-            api_key: config.api_key,
-            endpoint: self.endpoint,
+    ///
+    /// Returns an error if the configured endpoint is not a valid base URL or
+    /// if the underlying HTTP client cannot be constructed.
+    pub fn build(self, config: ClientConfig) -> Result<Client, Error> {
+        if self.endpoint.cannot_be_a_base() {
+            return Err(Error::InvalidConfig {
+                detail: format!("endpoint {} cannot be a base URL", self.endpoint),
+            });
+        }
+        let inner = match self.reqwest_client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::ClientBuilder::new()
+                    .redirect(reqwest::redirect::Policy::none())
+                    .timeout(self.timeout)
+                    .default_headers(self.default_headers);
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.read_timeout {
+                    builder = builder.read_timeout(timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                for proxy in self.proxies {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => Arc::new(ReqwestTransport::new(inner.clone())),
+        };
+        Ok(Client {
+            inner: Arc::new(ClientInner {
+                reqwest: inner,
+                transport,
+                api_key: config.api_key,
+                endpoint: self.endpoint,
+                retry_policy: self.retry_policy,
+                limiter: Limiter::new(&self.limits),
+                ingest_limiter: self.ingest_limits.as_ref().map(Limiter::new),
+                metrics: self.metrics,
+                auto_idempotency_keys: self.auto_idempotency_keys,
+                event_validation: self.event_validation,
+            }),
+            options: Arc::new(RequestOptions::default()),
+        })
+    }
+}
+
+/// Configures automatic retries of failed requests.
+///
+/// Requests that fail with a transport error, a `429 Too Many Requests`
+/// status, or a `5xx` status are retried with exponential backoff. Only
+/// requests that are safe to retry are retried: `GET` requests, and `POST`
+/// requests that carry an `Idempotency-Key` header.
+///
+/// If the API responds with a `Retry-After` header, the client waits for the
+/// specified duration instead of the computed backoff. If that duration
+/// exceeds the maximum delay, the request is not retried.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub(crate) max_attempts: u32,
+    base_delay: Duration,
+    pub(crate) max_delay: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy::DEFAULT
+    }
+}
+
+impl RetryPolicy {
+    /// The default retry policy.
+    ///
+    /// Makes up to three attempts, starting with a delay of 250ms and backing
+    /// off to a delay of at most 10s, with jitter.
+    ///
+    /// Exposed as a constant for use in constant evaluation contexts.
+    pub const DEFAULT: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(250),
+        max_delay: Duration::from_secs(10),
+        jitter: true,
+    };
+
+    /// A retry policy that never retries.
+    pub const NEVER: RetryPolicy = RetryPolicy::DEFAULT.max_attempts(1);
+
+    /// Sets the maximum number of attempts to make for a request, including
+    /// the initial attempt.
+    ///
+    /// A value of zero is treated as one.
+    pub const fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    ///
+    /// The delay doubles with each subsequent retry.
+    pub const fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Sets the maximum delay between attempts.
+    pub const fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Sets whether to randomize the delay between attempts.
+    ///
+    /// Jitter prevents many clients that failed at the same time from
+    /// retrying in lockstep. When enabled, each delay is chosen uniformly at
+    /// random from the upper half of the computed backoff.
+    pub const fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Computes the delay to wait after the specified attempt (starting at
+    /// one) fails.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        let delay = self.base_delay.saturating_mul(1 << exp).min(self.max_delay);
+        if self.jitter {
+            let half = delay / 2;
+            half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+        } else {
+            delay
+        }
+    }
+
+    /// Computes the delay to wait after the specified attempt (starting at
+    /// one) fails with a retryable error, honoring the delay the server asked
+    /// for via `Retry-After`, if any.
+    ///
+    /// Returns `None` if the server asked for a delay longer than the maximum
+    /// delay, in which case the request should not be retried.
+    pub(crate) fn delay_for(
+        &self,
+        attempt: u32,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        match retry_after {
+            // Retrying before the server asks us to is pointless, so give up
+            // if we're not willing to wait that long.
+            Some(delay) if delay > self.max_delay => None,
+            Some(delay) => Some(delay),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+}
+
+/// Limits on the rate and concurrency of requests made by a [`Client`].
+///
+/// Orb enforces per-account rate limits. Configuring matching limits on the
+/// client causes requests to wait for capacity rather than fail with `429 Too
+/// Many Requests` responses.
+#[derive(Debug, Clone)]
+pub struct RequestLimits {
+    pub(crate) requests_per_second: Option<u32>,
+    pub(crate) burst: Option<u32>,
+    pub(crate) max_in_flight: Option<usize>,
+}
+
+impl Default for RequestLimits {
+    fn default() -> RequestLimits {
+        RequestLimits::UNLIMITED
+    }
+}
+
+impl RequestLimits {
+    /// No limits.
+    ///
+    /// Exposed as a constant for use in constant evaluation contexts.
+    pub const UNLIMITED: RequestLimits = RequestLimits {
+        requests_per_second: None,
+        burst: None,
+        max_in_flight: None,
+    };
+
+    /// Limits the sustained rate of requests.
+    ///
+    /// The limit is enforced with a token bucket, so short bursts above the
+    /// rate are permitted up to the configured [burst
+    /// size](RequestLimits::burst). Each attempt of a retried request counts
+    /// against the limit.
+    pub const fn requests_per_second(mut self, requests_per_second: u32) -> Self {
+        self.requests_per_second = Some(requests_per_second);
+        self
+    }
+
+    /// Sets the maximum number of requests that may be made in a burst.
+    ///
+    /// Defaults to the number of requests per second. Has no effect unless
+    /// [`RequestLimits::requests_per_second`] is set.
+    pub const fn burst(mut self, burst: u32) -> Self {
+        self.burst = Some(burst);
+        self
+    }
+
+    /// Limits the number of requests that may be in flight at once.
+    ///
+    /// Zero is treated as one.
+    pub const fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(if max_in_flight == 0 { 1 } else { max_in_flight });
+        self
+    }
+}
+
+/// Options that apply to the requests made through a [`Client`] handle.
+///
+/// Apply options via [`Client::with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    pub(crate) idempotency_key: Option<String>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) headers: HeaderMap,
+    pub(crate) api_key: Option<String>,
+    pub(crate) retry_policy: Option<RetryPolicy>,
+}
+
+impl RequestOptions {
+    /// Creates a new set of request options that overrides nothing.
+    pub fn new() -> RequestOptions {
+        RequestOptions::default()
+    }
+
+    /// Sets the idempotency key to send with mutating requests.
+    ///
+    /// Orb processes at most one request with a given idempotency key, which
+    /// allows requests that fail midway to be safely retried. Requests with an
+    /// idempotency key are retried automatically according to the client's
+    /// [`RetryPolicy`].
+    ///
+    /// A key identifies a single logical request, so a handle with an
+    /// idempotency key should be used for only one mutating request. An
+    /// idempotency key set on a request type itself, as with
+    /// [`CreateCustomerRequest::idempotency_key`], takes precedence.
+    ///
+    /// [`CreateCustomerRequest::idempotency_key`]: crate::CreateCustomerRequest::idempotency_key
+    pub fn idempotency_key<S>(mut self, key: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Sets the timeout for each attempt of a request, overriding the timeout
+    /// configured via [`ClientBuilder::timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a header to send with each request.
+    ///
+    /// Replaces any header of the same name configured via
+    /// [`ClientBuilder::default_headers`] or a previous call to this method.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Sets the API key with which to authenticate, overriding the API key
+    /// configured via [`ClientConfig`].
+    pub fn api_key<S>(mut self, api_key: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Overrides the client's retry policy.
+    ///
+    /// Used by components like the [`EventIngester`] that retry requests
+    /// themselves, so that their attempts are not multiplied by the client's.
+    ///
+    /// [`EventIngester`]: crate::EventIngester
+    pub(crate) fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Overlays `other` on these options. Options set in `other` take
+    /// precedence.
+    pub(crate) fn merge(&mut self, other: RequestOptions) {
+        if other.idempotency_key.is_some() {
+            self.idempotency_key = other.idempotency_key;
+        }
+        if other.timeout.is_some() {
+            self.timeout = other.timeout;
+        }
+        if other.api_key.is_some() {
+            self.api_key = other.api_key;
+        }
+        if other.retry_policy.is_some() {
+            self.retry_policy = other.retry_policy;
+        }
+        for (name, value) in other.headers {
+            if let Some(name) = name {
+                self.headers.insert(name, value);
+            }
         }
     }
 }
@@ -66,6 +559,8 @@ impl ClientBuilder {
 #[derive(Debug, Clone)]
 pub struct ListParams {
     pub(crate) page_size: u64,
+    pub(crate) cursor: Option<String>,
+    pub(crate) prefetch: usize,
 }
 
 impl Default for ListParams {
@@ -78,7 +573,11 @@ impl ListParams {
     /// The default list parameters.
     ///
     /// Exposed as a constant for use in constant evaluation contexts.
-    pub const DEFAULT: ListParams = ListParams { page_size: 20 };
+    pub const DEFAULT: ListParams = ListParams {
+        page_size: 20,
+        cursor: None,
+        prefetch: 0,
+    };
 
     /// Sets the page size for the list operation.
     ///
@@ -94,4 +593,93 @@ impl ListParams {
         self.page_size = page_size;
         self
     }
+
+    /// Sets the number of pages to fetch ahead of the consumer.
+    ///
+    /// When nonzero, pages are fetched by a background task while the
+    /// consumer processes earlier pages, and up to `prefetch` fetched pages
+    /// are buffered until the consumer is ready for them. Pages are still
+    /// fetched one at a time, as the cursor for each page is reported by the
+    /// page before it, and are always delivered in order.
+    ///
+    /// Prefetching requires a Tokio runtime. Dropping the stream cancels any
+    /// outstanding fetches.
+    ///
+    /// The default is 0, which fetches each page only when the consumer
+    /// asks for it.
+    pub const fn prefetch(mut self, prefetch: usize) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Sets the cursor from which to start the list operation.
+    ///
+    /// Use the [`Page::next_cursor`] of the last page processed by an earlier
+    /// list operation to resume that operation where it left off. The other
+    /// parameters of the list operation must be unchanged.
+    ///
+    /// By default, the list operation starts from the beginning.
+    ///
+    /// [`Page::next_cursor`]: crate::Page::next_cursor
+    pub fn cursor<S>(mut self, cursor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> ClientConfig {
+        ClientConfig {
+            api_key: "test".into(),
+        }
+    }
+
+    #[test]
+    fn builder_accepts_custom_endpoint() {
+        let endpoint: Url = "http://localhost:8080/v1".parse().unwrap();
+        let client = ClientBuilder::default()
+            .endpoint(endpoint.clone())
+            .build(test_config())
+            .unwrap();
+        assert_eq!(client.inner.endpoint, endpoint);
+    }
+
+    #[test]
+    fn builder_rejects_endpoint_that_cannot_be_a_base() {
+        let res = ClientBuilder::default()
+            .endpoint("mailto:billing@example.com".parse().unwrap())
+            .build(test_config());
+        assert!(matches!(res, Err(Error::InvalidConfig { .. })));
+    }
+
+    #[test]
+    fn retry_backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy::DEFAULT
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(1))
+            .jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn retry_backoff_jitter_stays_in_upper_half() {
+        let policy = RetryPolicy::DEFAULT
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(1));
+        for attempt in 1..10 {
+            let unjittered = policy.clone().jitter(false).backoff(attempt);
+            let delay = policy.backoff(attempt);
+            assert!(delay >= unjittered / 2 && delay <= unjittered);
+        }
+    }
 }
@@ -14,6 +14,7 @@
 // limitations under the License.
 
 use std::fmt;
+use std::time::Duration;
 
 use reqwest::StatusCode;
 
@@ -23,14 +24,30 @@ use reqwest::StatusCode;
 #[derive(Debug)]
 pub enum Error {
     /// An error in the underlying transport.
-    Transport(reqwest::Error),
+    Transport(TransportError),
     /// An error returned by the API.
-    Api(ApiError),
+    Api(Box<ApiError>),
+    /// The API returned a response that could not be decoded.
+    Decode(Box<DecodeError>),
     /// The API returned an unexpected response.
     UnexpectedResponse {
         /// Details about the nature of the unexpected response.
         detail: String,
     },
+    /// The client was configured with invalid parameters.
+    InvalidConfig {
+        /// Details about the nature of the invalid configuration.
+        detail: String,
+    },
+    /// A request was constructed with invalid parameters.
+    InvalidRequest {
+        /// Details about the nature of the invalid request.
+        detail: String,
+    },
+    /// An error reading or writing an [`EventSpool`].
+    ///
+    /// [`EventSpool`]: crate::EventSpool
+    Spool(std::io::Error),
 }
 
 impl fmt::Display for Error {
@@ -38,14 +55,106 @@ impl fmt::Display for Error {
         match self {
             Error::Transport(e) => write!(f, "orb error: transport: {e}"),
             Error::Api(e) => write!(f, "orb error: api: {e}"),
+            Error::Decode(e) => write!(f, "orb error: decode: {e}"),
             Error::UnexpectedResponse { detail } => {
                 write!(f, "orb error: unexpected response: {detail}")
             }
+            Error::InvalidConfig { detail } => {
+                write!(f, "orb error: invalid configuration: {detail}")
+            }
+            Error::InvalidRequest { detail } => {
+                write!(f, "orb error: invalid request: {detail}")
+            }
+            Error::Spool(e) => write!(f, "orb error: spool: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(e) => Some(e),
+            Error::Api(e) => Some(&**e),
+            Error::Decode(e) => Some(&**e),
+            Error::Spool(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// Reports whether the error indicates that the requested resource does
+    /// not exist.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::Api(e) => match &e.kind {
+                Some(kind) => matches!(
+                    kind,
+                    ApiErrorKind::ResourceNotFound | ApiErrorKind::UrlNotFound
+                ),
+                None => e.status_code == StatusCode::NOT_FOUND,
+            },
+            _ => false,
+        }
+    }
+
+    /// Reports whether the error indicates that the request conflicts with
+    /// the current state of a resource, e.g., because the resource already
+    /// exists or because the request's idempotency key was already used.
+    pub fn is_conflict(&self) -> bool {
+        match self {
+            Error::Api(e) => {
+                e.status_code == StatusCode::CONFLICT
+                    || matches!(
+                        e.kind,
+                        Some(ApiErrorKind::ResourceConflict)
+                            | Some(ApiErrorKind::DuplicateResourceCreation)
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    /// Reports whether the request that caused the error may succeed if
+    /// retried.
+    ///
+    /// Transport errors like connection failures and timeouts are retryable,
+    /// as are API errors that indicate rate limiting or a server-side
+    /// failure.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Transport(e) => e.is_retryable(),
+            Error::Api(e) => is_retryable_status(e.status_code),
+            Error::Decode(e) => is_retryable_status(e.status_code),
+            _ => false,
+        }
+    }
+
+    /// Returns the ID that Orb assigned to the request that caused the error,
+    /// if known.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Error::Api(e) => e.request_id.as_deref(),
+            Error::Decode(e) => e.request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns how long the API asked the client to wait before retrying the
+    /// request, if it did so.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::Api(e) => e.retry_after,
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for Error {}
+/// Reports whether a response with the given status code may succeed if the
+/// request is retried.
+fn is_retryable_status(status_code: StatusCode) -> bool {
+    status_code == StatusCode::TOO_MANY_REQUESTS || status_code.is_server_error()
+}
 
 /// An error returned by the Orb API.
 ///
@@ -60,6 +169,79 @@ pub struct ApiError {
     pub detail: Option<String>,
     /// Errors that occurred while validating the request.
     pub validation_errors: Vec<String>,
+    /// The kind of error, if the API reported one.
+    pub kind: Option<ApiErrorKind>,
+    /// How long the API asked the client to wait before retrying the request,
+    /// as reported by the `Retry-After` header.
+    pub retry_after: Option<Duration>,
+    /// The ID that Orb assigned to the request, if reported.
+    ///
+    /// Include this ID when contacting Orb support about the error.
+    pub request_id: Option<String>,
+}
+
+/// The kind of an [`ApiError`].
+///
+/// Parsed from the `type` URI in Orb's error responses. For details, see:
+/// <https://docs.withorb.com/reference/error-responses>
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ApiErrorKind {
+    /// The request violated a constraint on the resource.
+    ConstraintViolation,
+    /// The request attempted to create a resource that already exists.
+    DuplicateResourceCreation,
+    /// The request used a feature that is not available to the account.
+    FeatureNotAvailable,
+    /// The request failed validation.
+    RequestValidation,
+    /// The request could not be authenticated.
+    Authentication,
+    /// The requested resource does not exist.
+    ResourceNotFound,
+    /// The requested URL does not exist.
+    UrlNotFound,
+    /// The request conflicts with the current state of the resource.
+    ResourceConflict,
+    /// The request body was too large.
+    RequestTooLarge,
+    /// The requested resource was too large to return.
+    ResourceTooLarge,
+    /// The request would return too many results.
+    TooManyResults,
+    /// The request exceeded the account's rate limit.
+    RateLimitExceeded,
+    /// The API encountered an internal error.
+    InternalServerError,
+    /// An unrecognized error type, containing the `type` URI.
+    Other(String),
+}
+
+impl ApiErrorKind {
+    /// Parses an error kind from the `type` URI of an error response.
+    ///
+    /// The URI's fragment identifies the error, prefixed by its status code,
+    /// as in `https://docs.withorb.com/reference/error-responses#404-resource-not-found`.
+    pub(crate) fn from_type_uri(uri: &str) -> ApiErrorKind {
+        let fragment = uri.rsplit_once('#').map_or(uri, |(_, fragment)| fragment);
+        let name = fragment.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-');
+        match name {
+            "constraint-violation" => ApiErrorKind::ConstraintViolation,
+            "duplicate-resource-creation" => ApiErrorKind::DuplicateResourceCreation,
+            "feature-not-available" => ApiErrorKind::FeatureNotAvailable,
+            "request-validation-error" => ApiErrorKind::RequestValidation,
+            "authentication-error" => ApiErrorKind::Authentication,
+            "resource-not-found" => ApiErrorKind::ResourceNotFound,
+            "url-not-found" => ApiErrorKind::UrlNotFound,
+            "resource-conflict" => ApiErrorKind::ResourceConflict,
+            "request-too-large" => ApiErrorKind::RequestTooLarge,
+            "resource-too-large" => ApiErrorKind::ResourceTooLarge,
+            "too-many-results" => ApiErrorKind::TooManyResults,
+            "too-many-requests" => ApiErrorKind::RateLimitExceeded,
+            "internal-server-error" => ApiErrorKind::InternalServerError,
+            _ => ApiErrorKind::Other(uri.into()),
+        }
+    }
 }
 
 impl fmt::Display for ApiError {
@@ -69,6 +251,9 @@ impl fmt::Display for ApiError {
             write!(f, ": {detail}")?;
         }
         write!(f, " (status {})", self.status_code)?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request ID {request_id})")?;
+        }
         write!(
             f,
             "; validation errors: {}",
@@ -79,14 +264,212 @@ impl fmt::Display for ApiError {
 
 impl std::error::Error for ApiError {}
 
+/// A response from the Orb API that could not be decoded.
+#[derive(Debug)]
+pub struct DecodeError {
+    /// The HTTP status code of the response.
+    pub status_code: StatusCode,
+    /// The path of the API endpoint that returned the response.
+    pub path: String,
+    /// The path within the response body to the value that could not be
+    /// decoded, e.g., `data[0].customer.id`.
+    pub json_path: String,
+    /// The underlying decoding error.
+    pub error: serde_json::Error,
+    /// The response body, truncated to at most [`DecodeError::MAX_BODY_LEN`]
+    /// bytes.
+    pub body: String,
+    /// The ID that Orb assigned to the request, if reported.
+    pub request_id: Option<String>,
+}
+
+impl DecodeError {
+    /// The maximum length of the response body retained in a
+    /// [`DecodeError`].
+    pub const MAX_BODY_LEN: usize = 1024;
+
+    pub(crate) fn new(
+        status_code: StatusCode,
+        path: String,
+        json_path: String,
+        error: serde_json::Error,
+        body: &[u8],
+        request_id: Option<String>,
+    ) -> DecodeError {
+        let mut body = String::from_utf8_lossy(body).into_owned();
+        if body.len() > Self::MAX_BODY_LEN {
+            let mut len = Self::MAX_BODY_LEN;
+            while !body.is_char_boundary(len) {
+                len -= 1;
+            }
+            body.truncate(len);
+        }
+        DecodeError {
+            status_code,
+            path,
+            json_path,
+            error,
+            body,
+            request_id,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unable to decode response from {} (status {}) at {}: {}",
+            self.path, self.status_code, self.json_path, self.error
+        )
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// An error in an [`HttpTransport`].
+///
+/// [`HttpTransport`]: crate::HttpTransport
+#[derive(Debug)]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+/// The kind of a [`TransportError`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransportErrorKind {
+    /// A connection to the API could not be established.
+    Connect,
+    /// The request timed out.
+    Timeout,
+    /// The request could not be sent or the response could not be read, e.g.,
+    /// because the connection was reset.
+    Request,
+    /// Any other error, e.g., an invalid request.
+    Other,
+}
+
+impl TransportError {
+    /// Creates a new transport error of the specified kind.
+    pub fn new<E>(kind: TransportErrorKind, source: E) -> TransportError
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        TransportError {
+            kind,
+            source: source.into(),
+        }
+    }
+
+    /// Returns the kind of the error.
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+
+    /// Reports whether the request that caused the error may succeed if
+    /// retried.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind,
+            TransportErrorKind::Connect | TransportErrorKind::Timeout | TransportErrorKind::Request
+        )
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(e: reqwest::Error) -> TransportError {
+        let kind = if e.is_timeout() {
+            TransportErrorKind::Timeout
+        } else if e.is_connect() {
+            TransportErrorKind::Connect
+        } else if e.is_request() || e.is_body() {
+            TransportErrorKind::Request
+        } else {
+            TransportErrorKind::Other
+        };
+        TransportError::new(kind, e)
+    }
+}
+
+impl From<TransportError> for Error {
+    fn from(e: TransportError) -> Error {
+        Error::Transport(e)
+    }
+}
+
 impl From<reqwest::Error> for Error {
     fn from(e: reqwest::Error) -> Error {
-        Error::Transport(e)
+        Error::Transport(e.into())
     }
 }
 
 impl From<ApiError> for Error {
     fn from(e: ApiError) -> Error {
-        Error::Api(e)
+        Error::Api(Box::new(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_error_truncates_body_at_char_boundary() {
+        let body = "é".repeat(DecodeError::MAX_BODY_LEN);
+        let error = serde_json::from_str::<()>("x").unwrap_err();
+        let e = DecodeError::new(
+            StatusCode::OK,
+            "/v1/customers".into(),
+            ".".into(),
+            error,
+            body.as_bytes(),
+            None,
+        );
+        assert_eq!(e.body.len(), DecodeError::MAX_BODY_LEN);
+        assert!(body.starts_with(&e.body));
+    }
+
+    #[test]
+    fn api_error_kind_parses_type_uris() {
+        for (uri, kind) in [
+            (
+                "https://docs.withorb.com/reference/error-responses#404-resource-not-found",
+                ApiErrorKind::ResourceNotFound,
+            ),
+            (
+                "https://docs.withorb.com/reference/error-responses#400-duplicate-resource-creation",
+                ApiErrorKind::DuplicateResourceCreation,
+            ),
+            (
+                "https://docs.withorb.com/reference/error-responses#429-too-many-requests",
+                ApiErrorKind::RateLimitExceeded,
+            ),
+            (
+                "https://docs.withorb.com/reference/error-responses#418-teapot",
+                ApiErrorKind::Other(
+                    "https://docs.withorb.com/reference/error-responses#418-teapot".into(),
+                ),
+            ),
+        ] {
+            assert_eq!(ApiErrorKind::from_type_uri(uri), kind);
+        }
     }
 }
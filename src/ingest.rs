@@ -0,0 +1,768 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE file at the
+// root of this repository, or online at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Background batching of event ingestion requests.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{self, Instant};
+
+use crate::client::events::{
+    EventValidationError, IdempotencyKeyNamespace, IngestEventResponse, IngestionMode,
+    OwnedIngestEventRequest,
+};
+use crate::client::Client;
+use crate::config::{RequestOptions, RetryPolicy};
+use crate::error::Error;
+use crate::spool::EventSpool;
+
+/// Configures an [`EventIngester`].
+#[derive(Debug, Clone)]
+pub struct IngesterConfig {
+    max_batch_size: usize,
+    flush_interval: Duration,
+    capacity: usize,
+    retry_policy: RetryPolicy,
+    spool: Option<EventSpool>,
+    key_namespace: Option<IdempotencyKeyNamespace>,
+}
+
+impl Default for IngesterConfig {
+    fn default() -> IngesterConfig {
+        IngesterConfig::DEFAULT
+    }
+}
+
+impl IngesterConfig {
+    /// The maximum number of events that Orb accepts in a single ingestion
+    /// request.
+    pub const MAX_BATCH_SIZE: usize = 500;
+
+    /// The default ingester configuration.
+    ///
+    /// Sends batches of up to [`IngesterConfig::MAX_BATCH_SIZE`] events at
+    /// least once per second, buffers up to 10,000 events, and makes up to
+    /// eight attempts to send each batch, backing off to a delay of at most
+    /// 30s.
+    ///
+    /// Exposed as a constant for use in constant evaluation contexts.
+    pub const DEFAULT: IngesterConfig = IngesterConfig {
+        max_batch_size: IngesterConfig::MAX_BATCH_SIZE,
+        flush_interval: Duration::from_secs(1),
+        capacity: 10_000,
+        retry_policy: RetryPolicy::DEFAULT
+            .max_attempts(8)
+            .max_delay(Duration::from_secs(30)),
+        spool: None,
+        key_namespace: None,
+    };
+
+    /// Sets the maximum number of events to send in a single request.
+    ///
+    /// The value is clamped to between one and
+    /// [`IngesterConfig::MAX_BATCH_SIZE`].
+    pub const fn max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = if max_batch_size == 0 {
+            1
+        } else if max_batch_size > IngesterConfig::MAX_BATCH_SIZE {
+            IngesterConfig::MAX_BATCH_SIZE
+        } else {
+            max_batch_size
+        };
+        self
+    }
+
+    /// Sets the maximum amount of time that an event is buffered before it
+    /// is sent.
+    pub const fn flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Sets the number of events that may be queued for the ingester before
+    /// [`EventIngester::send`] waits for room.
+    ///
+    /// A value of zero is treated as one.
+    pub const fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = if capacity == 0 { 1 } else { capacity };
+        self
+    }
+
+    /// Sets the policy for retrying batches that fail to send.
+    ///
+    /// Only failures that are [retryable](Error::is_retryable) are retried.
+    /// Retrying a batch is safe because Orb deduplicates events by their
+    /// idempotency key. This policy replaces the client's retry policy for
+    /// the ingester's requests.
+    pub const fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets a spool to which batches are written when Orb is unreachable.
+    ///
+    /// Batches that still fail with a retryable error after exhausting the
+    /// retry policy are written to the spool rather than reported as failed.
+    /// While the spool is nonempty, new batches are written to the spool
+    /// behind the existing ones, and the spool is drained in order at every
+    /// flush. Consider a retry policy with fewer attempts when using a spool,
+    /// so that batches are spooled promptly during an outage.
+    pub fn spool(mut self, spool: EventSpool) -> Self {
+        self.spool = Some(spool);
+        self
+    }
+
+    /// Derives the idempotency key of every event that is sent without one
+    /// from the event's content in the specified namespace.
+    ///
+    /// See [`IdempotencyKeyNamespace`] for details. Events sent with an
+    /// idempotency key keep it.
+    pub const fn idempotency_keys(mut self, namespace: IdempotencyKeyNamespace) -> Self {
+        self.key_namespace = Some(namespace);
+        self
+    }
+}
+
+/// A handle to a background task that batches events for ingestion.
+///
+/// Events sent to the ingester are buffered and sent to Orb in batches of up
+/// to the configured maximum batch size, or after the configured flush
+/// interval elapses, whichever comes first. Batches that fail with a
+/// retryable error are retried according to the configured retry policy.
+///
+/// Batches that ultimately fail are reported, along with their events, by the
+/// next call to [`EventIngester::flush`] or [`EventIngester::shutdown`], as
+/// are events that Orb rejects because they fail validation. Call `shutdown`
+/// before the process exits to guarantee that every event has either been
+/// ingested or returned to the caller. Alternatively, configure
+/// an [`EventSpool`] via [`IngesterConfig::spool`] to persist batches that
+/// cannot reach Orb to disk.
+///
+/// Handles are cheap to clone. All clones feed the same background task.
+///
+/// The ingester must be created within a [Tokio] runtime.
+///
+/// [Tokio]: https://tokio.rs
+#[derive(Debug, Clone)]
+pub struct EventIngester {
+    tx: mpsc::Sender<Command>,
+}
+
+#[derive(Debug)]
+enum Command {
+    Event(OwnedIngestEventRequest),
+    Flush(oneshot::Sender<Vec<IngestFailure>>),
+    Shutdown(oneshot::Sender<Vec<IngestFailure>>),
+}
+
+impl EventIngester {
+    /// Creates a new ingester that sends events via the specified client.
+    pub fn new(client: Client, config: IngesterConfig) -> EventIngester {
+        let (tx, rx) = mpsc::channel(config.capacity);
+        // The worker retries batches according to the ingester's retry
+        // policy, so disable the client's retries rather than multiply them.
+        let client = client.with_options(RequestOptions::new().retry_policy(RetryPolicy::NEVER));
+        let worker = Worker {
+            client,
+            config,
+            buffer: vec![],
+            failures: vec![],
+        };
+        tokio::spawn(worker.run(rx));
+        EventIngester { tx }
+    }
+
+    /// Queues an event for ingestion.
+    ///
+    /// Waits for room if the ingester's buffer is full. Returns the event in
+    /// an error if the ingester has been shut down.
+    pub async fn send(&self, event: OwnedIngestEventRequest) -> Result<(), SendError> {
+        self.tx
+            .send(Command::Event(event))
+            .await
+            .map_err(|e| match e.0 {
+                Command::Event(event) => SendError(event),
+                _ => unreachable!(),
+            })
+    }
+
+    /// Sends all events queued before the call, and waits for them to be
+    /// ingested.
+    ///
+    /// Returns an error that reports every failure since the last call to
+    /// `flush`, if there were any.
+    pub async fn flush(&self) -> Result<(), IngestError> {
+        self.request(Command::Flush).await
+    }
+
+    /// Shuts down the ingester, after sending all queued events.
+    ///
+    /// Once shutdown begins, further calls to [`EventIngester::send`] on any
+    /// handle to the ingester fail. Returns an error that reports every
+    /// failure since the last call to [`EventIngester::flush`], if there were
+    /// any.
+    pub async fn shutdown(self) -> Result<(), IngestError> {
+        self.request(Command::Shutdown).await
+    }
+
+    async fn request<F>(&self, command: F) -> Result<(), IngestError>
+    where
+        F: FnOnce(oneshot::Sender<Vec<IngestFailure>>) -> Command,
+    {
+        let (tx, rx) = oneshot::channel();
+        if self.tx.send(command(tx)).await.is_err() {
+            // The ingester has already shut down, and so has no events left
+            // to send.
+            return Ok(());
+        }
+        match rx.await {
+            Ok(failures) if failures.is_empty() => Ok(()),
+            Ok(failures) => Err(IngestError { failures }),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+struct Worker {
+    client: Client,
+    config: IngesterConfig,
+    buffer: Vec<OwnedIngestEventRequest>,
+    failures: Vec<IngestFailure>,
+}
+
+impl Worker {
+    async fn run(mut self, mut rx: mpsc::Receiver<Command>) {
+        // Drain any events left in the spool by a previous process promptly.
+        let mut deadline = self
+            .config
+            .spool
+            .as_ref()
+            .and_then(|spool| (!spool.is_empty()).then(Instant::now));
+        let mut shutdowns = vec![];
+        loop {
+            let command = match deadline {
+                Some(at) => match time::timeout_at(at, rx.recv()).await {
+                    Ok(command) => command,
+                    Err(_) => {
+                        // The flush interval elapsed.
+                        self.flush().await;
+                        deadline = self.idle_deadline();
+                        continue;
+                    }
+                },
+                None => rx.recv().await,
+            };
+            match command {
+                Some(Command::Event(mut event)) => {
+                    if let Some(namespace) = &self.config.key_namespace {
+                        if event.idempotency_key.is_empty() {
+                            event.idempotency_key = namespace.derive_key(&event.as_request());
+                        }
+                    }
+                    if self.buffer.is_empty() && deadline.is_none() {
+                        deadline = Some(Instant::now() + self.config.flush_interval);
+                    }
+                    self.buffer.push(event);
+                    if self.buffer.len() >= self.config.max_batch_size {
+                        self.flush().await;
+                        deadline = self.idle_deadline();
+                    }
+                }
+                Some(Command::Flush(reply)) => {
+                    self.flush().await;
+                    deadline = self.idle_deadline();
+                    let _ = reply.send(mem::take(&mut self.failures));
+                }
+                Some(Command::Shutdown(reply)) => {
+                    // Stop accepting new commands, but keep draining the
+                    // ones already queued so that no event is dropped.
+                    rx.close();
+                    shutdowns.push(reply);
+                }
+                None => break,
+            }
+        }
+        self.flush().await;
+        let mut failures = mem::take(&mut self.failures);
+        // Release the spool before acknowledging the shutdown, so that it can
+        // be reopened as soon as `shutdown` returns.
+        drop(self);
+        for reply in shutdowns {
+            let _ = reply.send(mem::take(&mut failures));
+        }
+    }
+
+    /// Returns when to next flush while no events are buffered.
+    ///
+    /// While the spool is nonempty, the worker periodically attempts to drain
+    /// it.
+    fn idle_deadline(&self) -> Option<Instant> {
+        match &self.config.spool {
+            Some(spool) if !spool.is_empty() => Some(Instant::now() + self.config.flush_interval),
+            _ => None,
+        }
+    }
+
+    /// Sends all spooled and buffered events, in batches of at most the
+    /// maximum batch size.
+    async fn flush(&mut self) {
+        let spool = self.config.spool.clone();
+        if let Some(spool) = &spool {
+            self.drain_spool(spool).await;
+        }
+        while !self.buffer.is_empty() {
+            let n = self.buffer.len().min(self.config.max_batch_size);
+            let batch: Vec<_> = self.buffer.drain(..n).collect();
+            match &spool {
+                // Queue the batch behind the events that are already spooled,
+                // so that events are ingested in order.
+                Some(spool) if !spool.is_empty() => self.spool(spool, batch).await,
+                _ => match self
+                    .send_batch(batch, self.config.retry_policy.max_attempts)
+                    .await
+                {
+                    Ok(rejected) => self
+                        .failures
+                        .extend(rejected.into_iter().map(IngestFailure::Rejected)),
+                    Err(failure) => match &spool {
+                        Some(spool) if failure.error.is_retryable() => {
+                            self.spool(spool, failure.events).await
+                        }
+                        _ => self.failures.push(IngestFailure::Batch(failure)),
+                    },
+                },
+            }
+        }
+    }
+
+    /// Sends the spooled batches, oldest first, until the spool is empty or a
+    /// batch fails with a retryable error.
+    ///
+    /// Makes only one attempt to send each batch, as the spool is retried at
+    /// every flush.
+    async fn drain_spool(&mut self, spool: &EventSpool) {
+        loop {
+            let (events, next) = match spool.peek_blocking().await {
+                Ok(Some(batch)) => batch,
+                Ok(None) => return,
+                Err(error) => {
+                    // A corrupt record has been moved out of the spool, so
+                    // carry on with the records that follow it.
+                    let corrupt = matches!(
+                        &error,
+                        Error::Spool(e) if e.kind() == io::ErrorKind::InvalidData
+                    );
+                    self.failures.push(IngestFailure::Spool(error));
+                    if corrupt {
+                        continue;
+                    }
+                    return;
+                }
+            };
+            match self.send_batch(events, 1).await {
+                Ok(rejected) => self
+                    .failures
+                    .extend(rejected.into_iter().map(IngestFailure::Rejected)),
+                Err(failure) if failure.error.is_retryable() => return,
+                // Orb rejected the batch, so it will never succeed. Remove it
+                // from the spool and report it.
+                Err(failure) => self.failures.push(IngestFailure::Batch(failure)),
+            }
+            if let Err(error) = spool.commit_blocking(next).await {
+                self.failures.push(IngestFailure::Spool(error));
+                return;
+            }
+        }
+    }
+
+    async fn spool(&mut self, spool: &EventSpool, events: Vec<OwnedIngestEventRequest>) {
+        if let Err((events, error)) = spool.append_blocking(events).await {
+            self.failures
+                .push(IngestFailure::Batch(FailedBatch { events, error }));
+        }
+    }
+
+    /// Sends a batch of events, returning the events that Orb rejected.
+    async fn send_batch(
+        &self,
+        events: Vec<OwnedIngestEventRequest>,
+        max_attempts: u32,
+    ) -> Result<Vec<RejectedEvent>, FailedBatch> {
+        let policy = &self.config.retry_policy;
+        let requests: Vec<_> = events.iter().map(|e| e.as_request()).collect();
+        let mut attempt = 0;
+        let error = loop {
+            attempt += 1;
+            match self
+                .client
+                .ingest_events(IngestionMode::Production, None, &requests)
+                .await
+            {
+                Ok(res) => return Ok(rejected_events(events, res)),
+                Err(e) if e.is_retryable() && attempt < max_attempts => {
+                    match policy.delay_for(attempt, e.retry_after()) {
+                        Some(delay) => time::sleep(delay).await,
+                        None => break e,
+                    }
+                }
+                Err(e) => break e,
+            }
+        };
+        Err(FailedBatch { events, error })
+    }
+}
+
+/// An error returned by [`EventIngester::send`] when the ingester has been
+/// shut down.
+///
+/// Contains the event that could not be sent.
+#[derive(Debug)]
+pub struct SendError(pub OwnedIngestEventRequest);
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("event ingester has shut down")
+    }
+}
+
+impl std::error::Error for SendError {}
+
+/// An error returned by [`EventIngester::flush`] and
+/// [`EventIngester::shutdown`] when batches of events could not be ingested
+/// or the spool could not be accessed.
+#[derive(Debug)]
+pub struct IngestError {
+    /// The failures, in the order in which they occurred.
+    pub failures: Vec<IngestFailure>,
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut events = 0;
+        let mut batches = 0;
+        let mut rejected = 0;
+        let mut spool_errors = 0;
+        for failure in &self.failures {
+            match failure {
+                IngestFailure::Batch(batch) => {
+                    events += batch.events.len();
+                    batches += 1;
+                }
+                IngestFailure::Rejected(_) => rejected += 1,
+                IngestFailure::Spool(_) => spool_errors += 1,
+            }
+        }
+        write!(f, "failed to ingest {events} events in {batches} batches")?;
+        if rejected > 0 {
+            write!(f, ", {rejected} events were rejected")?;
+        }
+        if spool_errors > 0 {
+            write!(f, ", encountered {spool_errors} spool errors")?;
+        }
+        if let Some(failure) = self.failures.last() {
+            match failure {
+                IngestFailure::Batch(batch) => write!(f, ": {}", batch.error)?,
+                IngestFailure::Rejected(rejected) => write!(
+                    f,
+                    ": event {} failed validation: {}",
+                    rejected.event.idempotency_key,
+                    rejected.validation_errors.join("; ")
+                )?,
+                IngestFailure::Spool(error) => write!(f, ": {error}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for IngestError {}
+
+/// A failure reported by an [`EventIngester`]. See [`IngestError`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum IngestFailure {
+    /// A batch of events could not be ingested.
+    Batch(FailedBatch),
+    /// Orb rejected an event because it failed validation. The other events
+    /// in its batch were ingested.
+    Rejected(RejectedEvent),
+    /// The spool could not be read or updated.
+    ///
+    /// If a spooled record is corrupt, it is moved to a side file, and the
+    /// error is of kind [`std::io::ErrorKind::InvalidData`]. Otherwise, the
+    /// affected events remain in the spool, and the ingester retries draining
+    /// it at the next flush.
+    Spool(Error),
+}
+
+/// A batch of events that could not be ingested. See [`IngestError`].
+#[derive(Debug)]
+pub struct FailedBatch {
+    /// The events in the batch.
+    pub events: Vec<OwnedIngestEventRequest>,
+    /// The error returned by the final attempt to send the batch.
+    pub error: Error,
+}
+
+/// An event that failed validation and was not ingested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedEvent {
+    /// The event.
+    pub event: OwnedIngestEventRequest,
+    /// The reasons that the event failed validation.
+    pub validation_errors: Vec<String>,
+    /// The problems found by [client-side
+    /// validation](crate::ClientBuilder::event_validation), if the event
+    /// was rejected before it was sent to Orb.
+    pub local_error: Option<EventValidationError>,
+}
+
+/// Returns the events in a batch that the response reports as having failed
+/// validation, either locally or in Orb.
+pub(crate) fn rejected_events(
+    events: Vec<OwnedIngestEventRequest>,
+    res: IngestEventResponse,
+) -> Vec<RejectedEvent> {
+    if res.validation_failed.is_empty() && res.locally_rejected.is_empty() {
+        return vec![];
+    }
+    let mut failures: BTreeMap<_, _> = res
+        .validation_failed
+        .into_iter()
+        .map(|f| (f.idempotency_key, f.validation_errors))
+        .collect();
+    let mut local_failures: BTreeMap<_, _> = res.locally_rejected.into_iter().collect();
+    let mut rejected = vec![];
+    for event in events {
+        if let Some(error) = local_failures.remove(&event.idempotency_key) {
+            rejected.push(RejectedEvent {
+                event,
+                validation_errors: error.issues.iter().map(|i| i.to_string()).collect(),
+                local_error: Some(error),
+            });
+        } else if let Some(validation_errors) = failures.remove(&event.idempotency_key) {
+            rejected.push(RejectedEvent {
+                event,
+                validation_errors,
+                local_error: None,
+            });
+        }
+    }
+    rejected
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+    use reqwest::StatusCode;
+
+    use super::*;
+    use crate::error::{TransportError, TransportErrorKind};
+    use crate::metrics::{ClientMetrics, IngestMetrics};
+    use crate::transport::mock::{test_event, MockTransport};
+    use crate::transport::HttpResponse;
+    use crate::{EventValidationIssue, EventValidationRules};
+
+    fn batches(transport: &MockTransport) -> Vec<Vec<String>> {
+        transport
+            .requests()
+            .iter()
+            .map(|req| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(req.body.as_deref().unwrap()).unwrap();
+                body["events"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|e| e["idempotency_key"].as_str().unwrap().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn ingester_batches_and_retries() {
+        let transport = MockTransport::default();
+        transport.respond(200, json!({}));
+        transport.fail(TransportError::new(TransportErrorKind::Connect, "refused"));
+        transport.respond(200, json!({}));
+        let config = IngesterConfig::DEFAULT
+            .max_batch_size(2)
+            .flush_interval(Duration::from_secs(3600))
+            .retry_policy(RetryPolicy::DEFAULT.base_delay(Duration::from_millis(1)));
+        let ingester = EventIngester::new(transport.client(), config);
+        for key in ["a", "b", "c"] {
+            ingester.send(test_event(key)).await.unwrap();
+        }
+        ingester.shutdown().await.unwrap();
+        assert_eq!(batches(&transport), [vec!["a", "b"], vec!["c"], vec!["c"]]);
+    }
+
+    #[tokio::test]
+    async fn ingester_gives_up_when_retry_after_exceeds_max_delay() {
+        let transport = MockTransport::default();
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        transport.respond_with(HttpResponse {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            headers,
+            body: serde_json::to_vec(&json!({ "title": "Too many requests" })).unwrap(),
+        });
+        let ingester = EventIngester::new(transport.client(), IngesterConfig::DEFAULT);
+        ingester.send(test_event("a")).await.unwrap();
+        let err = ingester.shutdown().await.unwrap_err();
+        assert_eq!(err.failures.len(), 1);
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn ingester_does_not_multiply_client_retries() {
+        let transport = MockTransport::default();
+        transport.fail(TransportError::new(TransportErrorKind::Connect, "refused"));
+        transport.fail(TransportError::new(TransportErrorKind::Connect, "refused"));
+        // Idempotency keys make the client consider ingestion requests safe
+        // to retry.
+        let client = transport.client_with(|builder| builder.auto_idempotency_keys(true));
+        let config = IngesterConfig::DEFAULT.retry_policy(
+            RetryPolicy::DEFAULT
+                .max_attempts(2)
+                .base_delay(Duration::from_millis(1)),
+        );
+        let ingester = EventIngester::new(client, config);
+        ingester.send(test_event("a")).await.unwrap();
+        let err = ingester.shutdown().await.unwrap_err();
+        assert_eq!(err.failures.len(), 1);
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn ingester_returns_rejected_events() {
+        #[derive(Debug, Default)]
+        struct Recorder(std::sync::Mutex<Vec<IngestMetrics>>);
+
+        impl ClientMetrics for Arc<Recorder> {
+            fn on_ingest(&self, ingest: &IngestMetrics) {
+                self.0.lock().unwrap().push(ingest.clone());
+            }
+        }
+
+        let transport = MockTransport::default();
+        transport.respond(
+            200,
+            json!({
+                "validation_failed": [
+                    { "idempotency_key": "b", "validation_errors": ["bad timestamp"] },
+                ],
+            }),
+        );
+        let recorder = Arc::new(Recorder::default());
+        let client = transport.client_with(|builder| builder.metrics(recorder.clone()));
+        let ingester = EventIngester::new(client, IngesterConfig::DEFAULT);
+        ingester.send(test_event("a")).await.unwrap();
+        ingester.send(test_event("b")).await.unwrap();
+        let err = ingester.shutdown().await.unwrap_err();
+        match &err.failures[..] {
+            [IngestFailure::Rejected(rejected)] => {
+                assert_eq!(rejected.event, test_event("b"));
+                assert_eq!(rejected.validation_errors, ["bad timestamp"]);
+                assert_eq!(rejected.local_error, None);
+            }
+            failures => panic!("unexpected failures: {failures:?}"),
+        }
+        assert_eq!(recorder.0.lock().unwrap()[0].rejected, 1);
+    }
+
+    #[tokio::test]
+    async fn ingester_returns_locally_rejected_events() {
+        let transport = MockTransport::default();
+        let client = transport
+            .client_with(|builder| builder.event_validation(EventValidationRules::DEFAULT));
+        let ingester = EventIngester::new(client, IngesterConfig::DEFAULT);
+        // The test event's timestamp is far outside the grace period.
+        ingester.send(test_event("a")).await.unwrap();
+        let err = ingester.shutdown().await.unwrap_err();
+        match &err.failures[..] {
+            [IngestFailure::Rejected(rejected)] => {
+                let issues = &rejected.local_error.as_ref().unwrap().issues;
+                assert!(matches!(
+                    issues[..],
+                    [EventValidationIssue::TimestampTooOld { .. }]
+                ));
+            }
+            failures => panic!("unexpected failures: {failures:?}"),
+        }
+        assert!(transport.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn ingester_derives_missing_idempotency_keys() {
+        let transport = MockTransport::default();
+        transport.respond(200, json!({}));
+        let namespace = IdempotencyKeyNamespace::new("replay");
+        let config = IngesterConfig::DEFAULT.idempotency_keys(namespace);
+        let ingester = EventIngester::new(transport.client(), config);
+        let keyless = OwnedIngestEventRequest {
+            idempotency_key: String::new(),
+            ..test_event("a")
+        };
+        ingester.send(keyless.clone()).await.unwrap();
+        ingester.send(test_event("a")).await.unwrap();
+        ingester.shutdown().await.unwrap();
+        let derived = namespace.derive_key(&keyless.as_request());
+        assert_eq!(batches(&transport), [vec![derived.as_str(), "a"]]);
+    }
+
+    #[tokio::test]
+    async fn ingester_flushes_on_interval() {
+        let transport = MockTransport::default();
+        transport.respond(200, json!({}));
+        let config = IngesterConfig::DEFAULT.flush_interval(Duration::from_millis(10));
+        let ingester = EventIngester::new(transport.client(), config);
+        ingester.send(test_event("a")).await.unwrap();
+        time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(batches(&transport), [vec!["a"]]);
+        ingester.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn ingester_returns_failed_batches() {
+        let transport = MockTransport::default();
+        transport.respond(400, json!({ "title": "bad request" }));
+        let ingester = EventIngester::new(transport.client(), IngesterConfig::DEFAULT);
+        let handle = ingester.clone();
+        ingester.send(test_event("a")).await.unwrap();
+        let err = ingester.flush().await.unwrap_err();
+        assert_eq!(err.failures.len(), 1);
+        match &err.failures[0] {
+            IngestFailure::Batch(batch) => {
+                assert_eq!(batch.events, [test_event("a")]);
+                assert!(matches!(batch.error, Error::Api(_)));
+            }
+            failure => panic!("unexpected failure: {failure:?}"),
+        }
+
+        ingester.shutdown().await.unwrap();
+        assert!(handle.send(test_event("b")).await.is_err());
+    }
+}
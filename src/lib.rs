@@ -39,11 +39,21 @@
 mod client;
 mod config;
 mod error;
+mod ingest;
+mod limit;
+mod metrics;
 mod serde;
+mod spool;
+mod trace;
+mod transport;
+mod typed_event;
 mod util;
 
+#[cfg(test)]
+extern crate self as orb_billing;
+
 pub use client::alerts::{Alert, AlertThreshold, AlertListParams, AlertType, CreateSubscriptionAlertRequest, UpdateAlertRequest};
-pub use client::backfill::{BackfillStatus, CreateBackfillParams, BackfillStatusResponse, ListBackfillsResponse};
+pub use client::backfill::{Backfill, BackfillError, BackfillListParams, BackfillStatus, CreateBackfillParams, BackfillStatusResponse, ListBackfillsResponse};
 pub use client::coupons::{Coupon, CouponListParams, Discount, RedeemedCoupon};
 pub use client::customers::{
     AddIncrementCreditLedgerEntryRequestParams, AddVoidCreditLedgerEntryRequestParams, Address,
@@ -54,27 +64,53 @@ pub use client::customers::{
     CustomerCostPriceBlockPriceGroup, CustomerCostPriceBlockUnitPrice,
     CustomerCostPriceBlockUnitPriceConfig, CustomerCreditBlock, CustomerId,
     CustomerPaymentProviderRequest, LedgerEntry, LedgerEntryRequest, PaymentProvider,
-    UpdateCustomerRequest, VoidReason, TaxConfiguration, AnrokTaxConfiguration
+    UpdateCustomerRequest, VoidReason, TaxConfiguration, AnrokTaxConfiguration,
+    CreateCustomerRequestBuilder, IncrementLedgerEntryRequestBuilder,
+    OwnedAddIncrementCreditLedgerEntryRequestParams, OwnedAddVoidCreditLedgerEntryRequestParams,
+    OwnedAddressRequest, OwnedCreateCustomerRequest, OwnedCreditLedgerInvoiceSettingsRequestParams,
+    OwnedCustomerId, OwnedCustomerPaymentProviderRequest, OwnedLedgerEntryRequest,
+    VoidLedgerEntryRequestBuilder,
 };
 pub use client::events::{
-    AmendEventRequest, Event, EventPropertyValue, EventSearchParams, IngestEventDebugResponse,
-    IngestEventRequest, IngestEventResponse, IngestionMode,
+    AmendEventRequest, AmendEventResponse, BulkEventConfig, BulkEventResult,
+    DeprecateEventResponse, Event, EventPropertyValue, EventSearchParams, EventSearchRangeConfig,
+    EventValidationError, EventValidationIssue, EventValidationRules, EventVolume,
+    EventVolumeParams, IdempotencyKeyNamespace, IngestEventDebugResponse, IngestEventOutcome,
+    IngestEventRequest, IngestEventRequestBuilder, IngestEventResponse, IngestValidationFailure,
+    IngestionMode, OwnedAmendEventRequest, OwnedIngestEventRequest,
 };
 pub use client::invoices::{
     Invoice, InvoiceLineItem, InvoiceCustomer, InvoiceListParams, InvoiceStatusFilter, InvoiceSubLineItem, InvoiceSubscription, MarkInvoiceAsPaidParams, UpcomingInvoice,
     InvoiceAdjustment
 };
 pub use client::marketplaces::ExternalMarketplace;
-pub use client::plans::{Plan, PlanId, PlanListParams};
+pub use client::plans::{OwnedPlanId, Plan, PlanId, PlanListParams};
 pub use client::prices::{AddAdjustmentInterval, AddPriceInterval, Adjustment, CreditAllocation, EditAdjustmentInterval, EditPriceInterval, FixedFeeQuantityTransition, NewAdjustment, NewMaximumAdjustment, 
     OverrideUnitPrice, Price, PriceType, PriceInterval, PriceOverride, QuantityOnlyPriceOverride, SubscriptionAdjustmentInterval, TieredPrice, 
     TransformPriceFilter, TransformPriceFilterField, TransformPriceFilterOperator, UnitPrice};
 pub use client::subscriptions::{
     BillingCycleAlignment, ChangeOption, CancelSubscriptionRequest, CreateSubscriptionRequest, PriceIntervalsRequest, SchedulePlanChangeRequest, Subscription, SubscriptionListParams,
     SubscriptionStatus, UpdatePriceQuantityRequest, UpdateSubscriptionRequest, FetchSubscriptionCostsRequest, FetchSubscriptionCostsResponse, SubscriptionCostsEntry,
-    FetchSubscriptionUsageRequest, FetchSubscriptionUsageResponse
+    FetchSubscriptionUsageRequest, FetchSubscriptionUsageResponse,
+    CreateSubscriptionRequestBuilder, OwnedCreateSubscriptionRequest,
+    OwnedSchedulePlanChangeRequest, OwnedSubscriptionExternalMarketplaceRequest,
+    SchedulePlanChangeRequestBuilder,
+};
+pub use client::taxes::{OwnedTaxIdRequest, TaxId, TaxIdRequest, TaxIdType};
+pub use client::{Client, Page};
+pub use config::{
+    ClientBuilder, ClientConfig, ListParams, RequestLimits, RequestOptions, RetryPolicy,
+};
+pub use error::{ApiError, ApiErrorKind, DecodeError, Error, TransportError, TransportErrorKind};
+pub use ingest::{
+    EventIngester, FailedBatch, IngestError, IngestFailure, IngesterConfig, RejectedEvent,
+    SendError,
 };
-pub use client::taxes::{TaxId, TaxIdRequest, TaxIdType};
-pub use client::Client;
-pub use config::{ClientBuilder, ClientConfig, ListParams};
-pub use error::{ApiError, Error};
+#[cfg(feature = "metrics")]
+pub use metrics::MetricsRecorder;
+pub use metrics::{ClientMetrics, IngestMetrics, RequestMetrics, StatusClass};
+pub use spool::{DrainSummary, EventSpool};
+pub use transport::{HttpRequest, HttpResponse, HttpTransport, ReqwestTransport};
+pub use typed_event::{EventConversionError, EventProperty, OrbEvent};
+#[cfg(feature = "derive")]
+pub use orb_billing_derive::OrbEvent;
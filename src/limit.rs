@@ -0,0 +1,138 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE file at the
+// root of this repository, or online at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::config::RequestLimits;
+
+/// Enforces a [`RequestLimits`].
+#[derive(Debug)]
+pub struct Limiter {
+    bucket: Option<Mutex<TokenBucket>>,
+    in_flight: Option<Semaphore>,
+}
+
+impl Limiter {
+    pub fn new(limits: &RequestLimits) -> Limiter {
+        Limiter {
+            bucket: limits.requests_per_second.map(|rate| {
+                let burst = limits.burst.unwrap_or(rate);
+                Mutex::new(TokenBucket::new(rate, burst, Instant::now()))
+            }),
+            in_flight: limits.max_in_flight.map(Semaphore::new),
+        }
+    }
+
+    /// Waits until a request is permitted by the limits.
+    ///
+    /// The returned permit must be held for as long as the request is in
+    /// flight.
+    pub async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        let permit = match &self.in_flight {
+            None => None,
+            Some(semaphore) => Some(
+                semaphore
+                    .acquire()
+                    .await
+                    .expect("semaphore is never closed"),
+            ),
+        };
+        if let Some(bucket) = &self.bucket {
+            let delay = bucket
+                .lock()
+                .expect("lock poisoned")
+                .reserve(Instant::now());
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+        }
+        permit
+    }
+}
+
+/// A token bucket that permits `rate` requests per second on average, with
+/// bursts of up to `capacity` requests.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    /// The number of available tokens, as of `updated_at`.
+    ///
+    /// May be negative, in which case the deficit is owed by callers that are
+    /// waiting for their reservation to mature.
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32, capacity: u32, now: Instant) -> TokenBucket {
+        let capacity = f64::from(capacity.max(1));
+        TokenBucket {
+            rate: f64::from(rate.max(1)),
+            capacity,
+            tokens: capacity,
+            updated_at: now,
+        }
+    }
+
+    /// Takes one token from the bucket, returning how long the caller must
+    /// wait before the token is available.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity);
+        self.updated_at = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_allows_bursts_then_spaces_requests() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10, 2, start);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(100));
+        assert_eq!(bucket.reserve(start), Duration::from_millis(200));
+
+        // After a long idle period, the bucket refills only to its capacity.
+        let later = start + Duration::from_secs(60);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn zero_max_in_flight_permits_one_request() {
+        let limiter = Limiter::new(&RequestLimits::UNLIMITED.max_in_flight(0));
+        let permit = limiter.acquire().await;
+        assert!(permit.is_some());
+        let second = tokio::time::timeout(Duration::from_millis(10), limiter.acquire()).await;
+        assert!(second.is_err());
+        drop(permit);
+        assert!(limiter.acquire().await.is_some());
+    }
+}
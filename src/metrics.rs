@@ -0,0 +1,200 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE file at the
+// root of this repository, or online at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::time::Duration;
+
+use reqwest::{Method, StatusCode};
+
+/// An observer of the requests made by a [`Client`].
+///
+/// Install an observer via [`ClientBuilder::metrics`] to feed counters and
+/// histograms. Observers are invoked inline on the request path, so they
+/// should be cheap and must not block.
+///
+/// All methods have default implementations that do nothing.
+///
+/// [`Client`]: crate::Client
+/// [`ClientBuilder::metrics`]: crate::ClientBuilder::metrics
+pub trait ClientMetrics: fmt::Debug + Send + Sync + 'static {
+    /// Called when a request completes, successfully or not.
+    ///
+    /// Called once per request, after any retries.
+    fn on_request(&self, request: &RequestMetrics<'_>) {
+        let _ = request;
+    }
+
+    /// Called when an event ingestion request succeeds.
+    fn on_ingest(&self, ingest: &IngestMetrics) {
+        let _ = ingest;
+    }
+}
+
+/// Describes a completed request. See [`ClientMetrics::on_request`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RequestMetrics<'a> {
+    /// The request method.
+    pub method: &'a Method,
+    /// The template of the request path, in which resource IDs are replaced
+    /// with a placeholder, as in `customers/{id}`.
+    pub endpoint: &'a str,
+    /// The class of the final response's status code.
+    pub status_class: StatusClass,
+    /// The final response's status code, if a response was received.
+    pub status: Option<StatusCode>,
+    /// The time taken by the request, including any retries.
+    pub duration: Duration,
+    /// The number of attempts made.
+    pub attempts: u32,
+    /// The size of the request body, in bytes.
+    pub request_bytes: usize,
+    /// The size of the final response body, in bytes.
+    pub response_bytes: usize,
+}
+
+/// The class of a response's status code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StatusClass {
+    /// A `2xx` status code.
+    Success,
+    /// A `4xx` status code.
+    ClientError,
+    /// A `5xx` status code.
+    ServerError,
+    /// Any other status code.
+    Other,
+    /// No response was received because of a transport error.
+    TransportError,
+}
+
+impl StatusClass {
+    pub(crate) fn from_status(status: StatusCode) -> StatusClass {
+        if status.is_success() {
+            StatusClass::Success
+        } else if status.is_client_error() {
+            StatusClass::ClientError
+        } else if status.is_server_error() {
+            StatusClass::ServerError
+        } else {
+            StatusClass::Other
+        }
+    }
+
+    /// Returns a short label for the status class, like `2xx`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusClass::Success => "2xx",
+            StatusClass::ClientError => "4xx",
+            StatusClass::ServerError => "5xx",
+            StatusClass::Other => "other",
+            StatusClass::TransportError => "transport_error",
+        }
+    }
+}
+
+impl fmt::Display for StatusClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Describes a successful event ingestion request. See
+/// [`ClientMetrics::on_ingest`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct IngestMetrics {
+    /// The number of events submitted.
+    pub events: usize,
+    /// The number of events that were new to Orb.
+    ///
+    /// Only known when the request is made with [`IngestionMode::Debug`].
+    ///
+    /// [`IngestionMode::Debug`]: crate::IngestionMode::Debug
+    pub accepted: Option<usize>,
+    /// The number of events that were already known to Orb.
+    ///
+    /// Only known when the request is made with [`IngestionMode::Debug`].
+    ///
+    /// [`IngestionMode::Debug`]: crate::IngestionMode::Debug
+    pub duplicate: Option<usize>,
+    /// The number of events that failed validation.
+    pub rejected: usize,
+}
+
+/// A [`ClientMetrics`] implementation that records to the [`metrics`] crate.
+///
+/// Records the following metrics:
+///
+/// * `orb_requests_total`, a counter labeled by `method`, `endpoint`, and
+///   `status_class`.
+/// * `orb_request_duration_seconds`, a histogram with the same labels.
+/// * `orb_request_attempts`, a histogram with the same labels.
+/// * `orb_request_bytes_total` and `orb_response_bytes_total`, counters
+///   labeled by `method` and `endpoint`.
+/// * `orb_ingest_events_total`, a counter of submitted events.
+/// * `orb_ingest_events_accepted_total` and
+///   `orb_ingest_events_duplicate_total`, counters of accepted and duplicate
+///   events, when known.
+/// * `orb_ingest_events_rejected_total`, a counter of events that failed
+///   validation.
+///
+/// Available only when the `metrics` feature is enabled.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Default)]
+pub struct MetricsRecorder {
+    _private: (),
+}
+
+#[cfg(feature = "metrics")]
+impl MetricsRecorder {
+    /// Creates a new recorder that records to the global [`metrics`]
+    /// recorder.
+    pub fn new() -> MetricsRecorder {
+        MetricsRecorder { _private: () }
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl ClientMetrics for MetricsRecorder {
+    fn on_request(&self, request: &RequestMetrics<'_>) {
+        let labels = [
+            ("method", request.method.to_string()),
+            ("endpoint", request.endpoint.to_string()),
+            ("status_class", request.status_class.as_str().to_string()),
+        ];
+        ::metrics::counter!("orb_requests_total", &labels).increment(1);
+        ::metrics::histogram!("orb_request_duration_seconds", &labels)
+            .record(request.duration.as_secs_f64());
+        ::metrics::histogram!("orb_request_attempts", &labels).record(f64::from(request.attempts));
+        let labels = &labels[..2];
+        ::metrics::counter!("orb_request_bytes_total", labels)
+            .increment(request.request_bytes as u64);
+        ::metrics::counter!("orb_response_bytes_total", labels)
+            .increment(request.response_bytes as u64);
+    }
+
+    fn on_ingest(&self, ingest: &IngestMetrics) {
+        ::metrics::counter!("orb_ingest_events_total").increment(ingest.events as u64);
+        if let Some(accepted) = ingest.accepted {
+            ::metrics::counter!("orb_ingest_events_accepted_total").increment(accepted as u64);
+        }
+        if let Some(duplicate) = ingest.duplicate {
+            ::metrics::counter!("orb_ingest_events_duplicate_total").increment(duplicate as u64);
+        }
+        ::metrics::counter!("orb_ingest_events_rejected_total").increment(ingest.rejected as u64);
+    }
+}
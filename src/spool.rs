@@ -0,0 +1,936 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE file at the
+// root of this repository, or online at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A durable, on-disk spool of events awaiting ingestion.
+//!
+//! The spool is a directory of append-only segment files, named by a
+//! zero-padded, monotonically increasing sequence number with a `.seg`
+//! extension. Each segment is a sequence of records, one per batch of events:
+//!
+//! ```text
+//! +------------+------------+--------------------+
+//! | len: u32le | crc: u32le | payload: len bytes |
+//! +------------+------------+--------------------+
+//! ```
+//!
+//! The payload is the JSON encoding of the batch, and the checksum is the
+//! CRC-32 of the payload. Every append is synced to disk before it is
+//! acknowledged.
+//!
+//! The position of the oldest unacknowledged record is stored in a `cursor`
+//! file, which is replaced atomically. Segments that precede the cursor are
+//! deleted. When the spool is opened, a record that was torn by a crash
+//! during an append is truncated away. A record that is corrupt anywhere else
+//! is moved to a side file with a `.corrupt` extension when it is read, and
+//! reported as an error, so that the records that follow it can still be
+//! drained.
+//!
+//! An exclusive lock on the `lock` file ensures that only one spool is open
+//! on the directory at a time.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use fs2::FileExt;
+
+use crate::client::events::{IngestionMode, OwnedIngestEventRequest};
+use crate::client::Client;
+use crate::error::Error;
+use crate::ingest::{self, RejectedEvent};
+
+const SEGMENT_EXTENSION: &str = "seg";
+const CORRUPT_EXTENSION: &str = "corrupt";
+const CURSOR_FILE: &str = "cursor";
+const LOCK_FILE: &str = "lock";
+const HEADER_LEN: u64 = 8;
+
+/// A durable, on-disk spool of events awaiting ingestion.
+///
+/// Install a spool via [`IngesterConfig::spool`] to have an
+/// [`EventIngester`] write batches that cannot reach Orb to disk rather than
+/// report them as failed. Spooled batches are drained in order once Orb is
+/// reachable again, including by an ingester in a later process that opens
+/// the same directory.
+///
+/// Spooled events are resent if the process crashes after a batch is
+/// ingested but before the spool records that it was, which is safe because
+/// Orb deduplicates events by their idempotency key.
+///
+/// Handles are cheap to clone. Only one spool may be open on a given
+/// directory at a time, whether in the same process or another.
+///
+/// [`IngesterConfig::spool`]: crate::IngesterConfig::spool
+/// [`EventIngester`]: crate::EventIngester
+#[derive(Debug, Clone)]
+pub struct EventSpool {
+    inner: Arc<SpoolInner>,
+}
+
+#[derive(Debug)]
+struct SpoolInner {
+    dir: PathBuf,
+    max_segment_bytes: u64,
+    state: Mutex<SpoolState>,
+    /// Holds the exclusive lock on the directory until the spool is dropped.
+    _lock: File,
+}
+
+#[derive(Debug)]
+struct SpoolState {
+    /// The sequence numbers of the segments, in order. The last segment is
+    /// the one being appended to. Never empty.
+    segments: VecDeque<u64>,
+    /// The position of the oldest unacknowledged record in the first
+    /// segment.
+    read_offset: u64,
+    /// The segment being appended to.
+    writer: File,
+    /// The length of the segment being appended to.
+    write_len: u64,
+    /// Whether a failed append could not be rolled back, leaving a partial
+    /// record at the end of the segment.
+    poisoned: bool,
+    /// The number of bytes of the next record to write before failing, to
+    /// test recovery from failed appends.
+    #[cfg(test)]
+    fail_next_append: Option<usize>,
+}
+
+impl EventSpool {
+    /// The default maximum size of a segment file, in bytes.
+    pub const DEFAULT_MAX_SEGMENT_BYTES: u64 = 16 << 20;
+
+    /// Opens the spool in the specified directory, creating the directory if
+    /// it does not exist.
+    ///
+    /// Recovers from a crash during a previous append by truncating any
+    /// partially written record. Fails if another spool is open on the
+    /// directory.
+    pub fn open<P>(dir: P) -> Result<EventSpool, Error>
+    where
+        P: AsRef<Path>,
+    {
+        EventSpool::open_with_segment_size(dir, EventSpool::DEFAULT_MAX_SEGMENT_BYTES)
+    }
+
+    /// Like [`EventSpool::open`], but rolls over to a new segment file once
+    /// the current segment reaches `max_segment_bytes`.
+    pub fn open_with_segment_size<P>(dir: P, max_segment_bytes: u64) -> Result<EventSpool, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let dir = dir.as_ref().to_path_buf();
+        let lock = lock(&dir).map_err(Error::Spool)?;
+        let state = recover(&dir).map_err(Error::Spool)?;
+        Ok(EventSpool {
+            inner: Arc::new(SpoolInner {
+                dir,
+                max_segment_bytes,
+                state: Mutex::new(state),
+                _lock: lock,
+            }),
+        })
+    }
+
+    /// Reports whether the spool contains no unacknowledged events.
+    pub fn is_empty(&self) -> bool {
+        let state = self.state();
+        state.segments.len() == 1 && state.read_offset >= state.write_len
+    }
+
+    /// Durably appends a batch of events to the spool.
+    ///
+    /// Performs blocking file I/O.
+    pub fn append(&self, events: &[OwnedIngestEventRequest]) -> Result<(), Error> {
+        let payload = serde_json::to_vec(events).expect("events serialize");
+        self.append_record(&payload).map_err(Error::Spool)
+    }
+
+    /// Ingests the spooled events via the specified client, oldest first,
+    /// until the spool is empty or a request fails.
+    ///
+    /// Events that Orb rejects because they fail validation are removed from
+    /// the spool and returned in the summary. A corrupt record is moved to a
+    /// side file and reported as an error of kind
+    /// [`io::ErrorKind::InvalidData`], and draining again resumes with the
+    /// record that follows it. Performs the file I/O on a blocking thread.
+    pub async fn drain(&self, client: &Client) -> Result<DrainSummary, Error> {
+        let mut summary = DrainSummary::default();
+        while let Some((events, next)) = self.peek_blocking().await? {
+            let requests: Vec<_> = events.iter().map(|e| e.as_request()).collect();
+            let res = client
+                .ingest_events(IngestionMode::Production, None, &requests)
+                .await?;
+            self.commit_blocking(next).await?;
+            let total = events.len();
+            let rejected = ingest::rejected_events(events, res);
+            summary.ingested += total - rejected.len();
+            summary.rejected.extend(rejected);
+        }
+        Ok(summary)
+    }
+
+    /// Like [`EventSpool::append`], but performs the I/O on a blocking
+    /// thread.
+    pub(crate) async fn append_blocking(
+        &self,
+        events: Vec<OwnedIngestEventRequest>,
+    ) -> Result<(), (Vec<OwnedIngestEventRequest>, Error)> {
+        let spool = self.clone();
+        tokio::task::spawn_blocking(move || match spool.append(&events) {
+            Ok(()) => Ok(()),
+            Err(e) => Err((events, e)),
+        })
+        .await
+        .expect("spool append does not panic")
+    }
+
+    /// Like [`EventSpool::peek`], but performs the I/O on a blocking thread.
+    pub(crate) async fn peek_blocking(
+        &self,
+    ) -> Result<Option<(Vec<OwnedIngestEventRequest>, u64)>, Error> {
+        let spool = self.clone();
+        tokio::task::spawn_blocking(move || spool.peek())
+            .await
+            .expect("spool peek does not panic")
+    }
+
+    /// Like [`EventSpool::commit`], but performs the I/O on a blocking
+    /// thread.
+    pub(crate) async fn commit_blocking(&self, next: u64) -> Result<(), Error> {
+        let spool = self.clone();
+        tokio::task::spawn_blocking(move || spool.commit(next))
+            .await
+            .expect("spool commit does not panic")
+    }
+
+    /// Returns the oldest unacknowledged batch of events, along with the
+    /// position of the following record, which is to be passed to
+    /// [`EventSpool::commit`] once the batch is ingested.
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the record
+    /// is corrupt, after moving it to a side file, so that the next call
+    /// returns the record that follows it.
+    pub(crate) fn peek(&self) -> Result<Option<(Vec<OwnedIngestEventRequest>, u64)>, Error> {
+        let mut state = self.state();
+        loop {
+            let segment = state.segments[0];
+            let path = segment_path(&self.inner.dir, segment);
+            let mut file = File::open(&path).map_err(Error::Spool)?;
+            file.seek(SeekFrom::Start(state.read_offset))
+                .map_err(Error::Spool)?;
+            let (payload, next) = match read_record(&mut file) {
+                Ok(Some(payload)) => {
+                    let next = state.read_offset + HEADER_LEN + payload.len() as u64;
+                    (payload, next)
+                }
+                // The first segment is exhausted. Move on to the next
+                // segment, if there is one.
+                Ok(None) if state.segments.len() > 1 => {
+                    self.advance(&mut state).map_err(Error::Spool)?;
+                    continue;
+                }
+                Ok(None) => return Ok(None),
+                // Appends are synced under the lock and rolled back if they
+                // fail, so a torn record here means the segment is corrupt.
+                // Either way, the file is positioned at the end of the
+                // corrupt data.
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::UnexpectedEof | io::ErrorKind::InvalidData
+                    ) =>
+                {
+                    let end = file.stream_position().map_err(Error::Spool)?;
+                    return Err(self.quarantine(&mut state, &mut file, end, e));
+                }
+                Err(e) => return Err(Error::Spool(e)),
+            };
+            return match serde_json::from_slice(&payload) {
+                Ok(events) => Ok(Some((events, next))),
+                Err(e) => {
+                    let e = io::Error::new(io::ErrorKind::InvalidData, e);
+                    Err(self.quarantine(&mut state, &mut file, next, e))
+                }
+            };
+        }
+    }
+
+    /// Acknowledges every record before position `next` in the first
+    /// segment.
+    pub(crate) fn commit(&self, next: u64) -> Result<(), Error> {
+        let mut state = self.state();
+        self.acknowledge(&mut state, next).map_err(Error::Spool)
+    }
+
+    fn acknowledge(&self, state: &mut SpoolState, next: u64) -> io::Result<()> {
+        state.read_offset = next;
+        let len = if state.segments.len() == 1 {
+            state.write_len
+        } else {
+            fs::metadata(segment_path(&self.inner.dir, state.segments[0]))?.len()
+        };
+        if state.segments.len() > 1 && next >= len {
+            self.advance(state)
+        } else {
+            write_cursor(&self.inner.dir, state.segments[0], next)
+        }
+    }
+
+    /// Moves the corrupt data between the read offset and `end` in the first
+    /// segment to the segment's side file and acknowledges it.
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] that reports
+    /// `error` and the side file, or the error that prevented the data from
+    /// being moved.
+    fn quarantine(
+        &self,
+        state: &mut SpoolState,
+        file: &mut File,
+        end: u64,
+        error: io::Error,
+    ) -> Error {
+        let segment = state.segments[0];
+        let path = self
+            .inner
+            .dir
+            .join(format!("{segment:020}.{CORRUPT_EXTENSION}"));
+        let start = state.read_offset;
+        let mut data = vec![];
+        let res = file
+            .seek(SeekFrom::Start(start))
+            .and_then(|_| file.take(end - start).read_to_end(&mut data))
+            .and_then(|_| {
+                let mut side = OpenOptions::new().create(true).append(true).open(&path)?;
+                side.write_all(&data)?;
+                side.sync_data()?;
+                sync_dir(&self.inner.dir)
+            })
+            .and_then(|()| self.acknowledge(state, end));
+        match res {
+            Ok(()) => Error::Spool(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("corrupt spool record moved to {}: {error}", path.display()),
+            )),
+            Err(e) => Error::Spool(e),
+        }
+    }
+
+    /// Deletes the first segment and moves the cursor to the start of the
+    /// next segment.
+    fn advance(&self, state: &mut SpoolState) -> io::Result<()> {
+        let segment = state.segments.pop_front().expect("segments never empty");
+        state.read_offset = 0;
+        // Persist the cursor before deleting the segment, so that a crash in
+        // between leaves a stale segment rather than a cursor that points
+        // into a deleted one.
+        write_cursor(&self.inner.dir, state.segments[0], 0)?;
+        fs::remove_file(segment_path(&self.inner.dir, segment))?;
+        sync_dir(&self.inner.dir)
+    }
+
+    fn append_record(&self, payload: &[u8]) -> io::Result<()> {
+        let len = u32::try_from(payload.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "batch too large"))?;
+        let mut record = Vec::with_capacity(HEADER_LEN as usize + payload.len());
+        record.extend(len.to_le_bytes());
+        record.extend(crc32(payload).to_le_bytes());
+        record.extend(payload);
+
+        let mut state = self.state();
+        if state.poisoned {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "spool is poisoned by an append that could not be rolled back",
+            ));
+        }
+        if state.write_len > 0
+            && state.write_len + record.len() as u64 > self.inner.max_segment_bytes
+        {
+            let segment = state.segments.back().expect("segments never empty") + 1;
+            state.writer = create_segment(&self.inner.dir, segment)?;
+            state.write_len = 0;
+            state.segments.push_back(segment);
+        }
+        if let Err(e) = write_record(&mut state, &record) {
+            // Remove any partially written record, so that later appends
+            // follow the last complete record.
+            let write_len = state.write_len;
+            let rollback = state
+                .writer
+                .set_len(write_len)
+                .and_then(|()| state.writer.seek(SeekFrom::Start(write_len)))
+                .and_then(|_| state.writer.sync_data());
+            if rollback.is_err() {
+                state.poisoned = true;
+            }
+            return Err(e);
+        }
+        state.write_len += record.len() as u64;
+        Ok(())
+    }
+
+    fn state(&self) -> MutexGuard<'_, SpoolState> {
+        self.inner.state.lock().expect("lock poisoned")
+    }
+}
+
+/// The outcome of [`EventSpool::drain`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DrainSummary {
+    /// The number of events that Orb accepted, including those it already
+    /// knew.
+    pub ingested: usize,
+    /// The events that Orb rejected because they failed validation.
+    pub rejected: Vec<RejectedEvent>,
+}
+
+/// Writes a record to the end of the segment being appended to and syncs it.
+fn write_record(state: &mut SpoolState, record: &[u8]) -> io::Result<()> {
+    #[cfg(test)]
+    if let Some(n) = state.fail_next_append.take() {
+        state.writer.write_all(&record[..n])?;
+        return Err(io::Error::new(io::ErrorKind::Other, "injected failure"));
+    }
+    state.writer.write_all(record)?;
+    state.writer.sync_data()
+}
+
+/// Creates the directory if it does not exist, and takes an exclusive lock
+/// on it.
+fn lock(dir: &Path) -> io::Result<File> {
+    fs::create_dir_all(dir)?;
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(dir.join(LOCK_FILE))?;
+    file.try_lock_exclusive().map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "spool directory {} is locked by another spool: {e}",
+                dir.display()
+            ),
+        )
+    })?;
+    Ok(file)
+}
+
+/// Loads the spool's state from disk, creating the first segment if
+/// necessary.
+fn recover(dir: &Path) -> io::Result<SpoolState> {
+    let mut segments = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(SEGMENT_EXTENSION) {
+            continue;
+        }
+        if let Some(segment) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse::<u64>().ok())
+        {
+            segments.push(segment);
+        }
+    }
+    segments.sort_unstable();
+    let mut segments = VecDeque::from(segments);
+
+    // Discard the segments that precede the cursor, which may linger after a
+    // crash.
+    let (cursor_segment, mut read_offset) = read_cursor(dir)?.unwrap_or((0, 0));
+    while segments.front().is_some_and(|s| *s < cursor_segment) {
+        let segment = segments.pop_front().expect("checked above");
+        fs::remove_file(segment_path(dir, segment))?;
+    }
+    if segments.front() != Some(&cursor_segment) {
+        read_offset = 0;
+    }
+
+    let (writer, write_len) = match segments.back() {
+        Some(segment) => {
+            // Truncate any record that was torn by a crash during an append.
+            let path = segment_path(dir, *segment);
+            let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+            let len = file.metadata()?.len();
+            let mut valid_len = 0;
+            loop {
+                match read_record(&mut file) {
+                    Ok(Some(_)) => valid_len = file.stream_position()?,
+                    Ok(None) => break,
+                    // A torn record can only be the last in the segment.
+                    Err(e)
+                        if matches!(
+                            e.kind(),
+                            io::ErrorKind::UnexpectedEof | io::ErrorKind::InvalidData
+                        ) && file.stream_position()? == len =>
+                    {
+                        break
+                    }
+                    // Any other corrupt record is kept, along with the
+                    // records that follow it, and moved aside when it is
+                    // read.
+                    Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                        valid_len = file.stream_position()?
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            if len != valid_len {
+                file.set_len(valid_len)?;
+                file.sync_all()?;
+            }
+            file.seek(SeekFrom::Start(valid_len))?;
+            (file, valid_len)
+        }
+        None => {
+            let segment = cursor_segment;
+            segments.push_back(segment);
+            (create_segment(dir, segment)?, 0)
+        }
+    };
+    if segments.len() == 1 {
+        read_offset = read_offset.min(write_len);
+    }
+
+    Ok(SpoolState {
+        segments,
+        read_offset,
+        writer,
+        write_len,
+        poisoned: false,
+        #[cfg(test)]
+        fail_next_append: None,
+    })
+}
+
+/// Reads the record at the file's current position.
+///
+/// Returns `None` at the end of the file. Returns an error of kind
+/// [`io::ErrorKind::UnexpectedEof`] if the file ends partway through the
+/// record, or of kind [`io::ErrorKind::InvalidData`] if the record fails its
+/// checksum.
+fn read_record(file: &mut File) -> io::Result<Option<Vec<u8>>> {
+    let mut header = [0; HEADER_LEN as usize];
+    match read_full(file, &mut header)? {
+        0 => return Ok(None),
+        n if n < header.len() => return Err(torn_record()),
+        _ => (),
+    }
+    let len = u32::from_le_bytes(header[..4].try_into().expect("4 bytes"));
+    let crc = u32::from_le_bytes(header[4..].try_into().expect("4 bytes"));
+    // Check the length against the file before allocating, as a corrupt
+    // header may claim a huge payload.
+    let remaining = file
+        .metadata()?
+        .len()
+        .saturating_sub(file.stream_position()?);
+    if u64::from(len) > remaining {
+        file.seek(SeekFrom::End(0))?;
+        return Err(torn_record());
+    }
+    let mut payload = vec![0; len as usize];
+    if read_full(file, &mut payload)? < payload.len() {
+        return Err(torn_record());
+    }
+    if crc32(&payload) != crc {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "spool record fails its checksum",
+        ));
+    }
+    Ok(Some(payload))
+}
+
+fn torn_record() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "spool record is incomplete")
+}
+
+/// Reads from the file until `buf` is full or the file ends, returning the
+/// number of bytes read.
+fn read_full(file: &mut File, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn segment_path(dir: &Path, segment: u64) -> PathBuf {
+    dir.join(format!("{segment:020}.{SEGMENT_EXTENSION}"))
+}
+
+fn create_segment(dir: &Path, segment: u64) -> io::Result<File> {
+    let file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(segment_path(dir, segment))?;
+    sync_dir(dir)?;
+    Ok(file)
+}
+
+fn read_cursor(dir: &Path) -> io::Result<Option<(u64, u64)>> {
+    let contents = match fs::read_to_string(dir.join(CURSOR_FILE)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid spool cursor");
+    let (segment, offset) = contents.trim().split_once(' ').ok_or_else(invalid)?;
+    let segment = segment.parse().map_err(|_| invalid())?;
+    let offset = offset.parse().map_err(|_| invalid())?;
+    Ok(Some((segment, offset)))
+}
+
+/// Atomically replaces the cursor file.
+fn write_cursor(dir: &Path, segment: u64, offset: u64) -> io::Result<()> {
+    let tmp = dir.join(format!("{CURSOR_FILE}.tmp"));
+    let mut file = File::create(&tmp)?;
+    file.write_all(format!("{segment} {offset}\n").as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp, dir.join(CURSOR_FILE))?;
+    sync_dir(dir)
+}
+
+/// Syncs a directory, so that the creation, deletion, or renaming of the
+/// files within it is durable.
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+/// Computes the CRC-32 (IEEE) checksum of `data`.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::error::{TransportError, TransportErrorKind};
+    use crate::transport::mock::{test_event, MockTransport};
+    use crate::{EventIngester, IngestFailure, IngesterConfig, RetryPolicy};
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> TempDir {
+            let dir = std::env::temp_dir().join(format!("orb-spool-{}", uuid::Uuid::new_v4()));
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn keys(events: &[OwnedIngestEventRequest]) -> Vec<&str> {
+        events.iter().map(|e| e.idempotency_key.as_str()).collect()
+    }
+
+    #[test]
+    fn crc32_matches_reference() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn spool_survives_reopening() {
+        let dir = TempDir::new();
+        let spool = EventSpool::open(&dir.0).unwrap();
+        assert!(spool.is_empty());
+        spool.append(&[test_event("a"), test_event("b")]).unwrap();
+        spool.append(&[test_event("c")]).unwrap();
+        let (events, next) = spool.peek().unwrap().unwrap();
+        assert_eq!(keys(&events), ["a", "b"]);
+        spool.commit(next).unwrap();
+        drop(spool);
+
+        let spool = EventSpool::open(&dir.0).unwrap();
+        assert!(!spool.is_empty());
+        let (events, next) = spool.peek().unwrap().unwrap();
+        assert_eq!(keys(&events), ["c"]);
+        assert_eq!(events[0], test_event("c"));
+        spool.commit(next).unwrap();
+        assert!(spool.is_empty());
+        assert!(spool.peek().unwrap().is_none());
+    }
+
+    #[test]
+    fn spool_truncates_torn_records() {
+        let dir = TempDir::new();
+        let spool = EventSpool::open(&dir.0).unwrap();
+        spool.append(&[test_event("a")]).unwrap();
+        drop(spool);
+
+        // Simulate a crash partway through appending a record.
+        let path = segment_path(&dir.0, 0);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[42, 0, 0, 0, 1, 2]).unwrap();
+        drop(file);
+
+        let spool = EventSpool::open(&dir.0).unwrap();
+        spool.append(&[test_event("b")]).unwrap();
+        let (events, next) = spool.peek().unwrap().unwrap();
+        assert_eq!(keys(&events), ["a"]);
+        spool.commit(next).unwrap();
+        let (events, _) = spool.peek().unwrap().unwrap();
+        assert_eq!(keys(&events), ["b"]);
+    }
+
+    #[test]
+    fn spool_rolls_back_failed_appends() {
+        let dir = TempDir::new();
+        let spool = EventSpool::open(&dir.0).unwrap();
+        spool.append(&[test_event("a")]).unwrap();
+        spool.state().fail_next_append = Some(5);
+        assert!(spool.append(&[test_event("b")]).is_err());
+        spool.append(&[test_event("c")]).unwrap();
+        drop(spool);
+
+        let spool = EventSpool::open(&dir.0).unwrap();
+        let mut seen = vec![];
+        while let Some((events, next)) = spool.peek().unwrap() {
+            seen.extend(events);
+            spool.commit(next).unwrap();
+        }
+        assert_eq!(keys(&seen), ["a", "c"]);
+    }
+
+    #[test]
+    fn spool_reports_corrupt_records() {
+        let dir = TempDir::new();
+        let spool = EventSpool::open_with_segment_size(&dir.0, 1).unwrap();
+        spool.append(&[test_event("a")]).unwrap();
+        spool.append(&[test_event("b")]).unwrap();
+
+        // Flip a byte in the payload of the record in the first segment.
+        let path = segment_path(&dir.0, 0);
+        let mut contents = fs::read(&path).unwrap();
+        contents[HEADER_LEN as usize] ^= 0xFF;
+        fs::write(&path, contents).unwrap();
+
+        match spool.peek() {
+            Err(Error::Spool(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            res => panic!("unexpected result: {res:?}"),
+        }
+        // The corrupt record is moved aside, and the spool moves on.
+        assert!(!path.exists());
+        assert!(dir.0.join("00000000000000000000.corrupt").exists());
+        let (events, _) = spool.peek().unwrap().unwrap();
+        assert_eq!(keys(&events), ["b"]);
+    }
+
+    #[test]
+    fn spool_does_not_truncate_corrupt_records_on_open() {
+        let dir = TempDir::new();
+        let spool = EventSpool::open(&dir.0).unwrap();
+        spool.append(&[test_event("a")]).unwrap();
+        spool.append(&[test_event("b")]).unwrap();
+        drop(spool);
+
+        let path = segment_path(&dir.0, 0);
+        let mut contents = fs::read(&path).unwrap();
+        let len = contents.len();
+        contents[HEADER_LEN as usize] ^= 0xFF;
+        fs::write(&path, contents).unwrap();
+
+        let spool = EventSpool::open(&dir.0).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), len as u64);
+        spool.append(&[test_event("c")]).unwrap();
+        assert!(spool.peek().is_err());
+        let mut seen = vec![];
+        while let Some((events, next)) = spool.peek().unwrap() {
+            seen.extend(events);
+            spool.commit(next).unwrap();
+        }
+        assert_eq!(keys(&seen), ["b", "c"]);
+    }
+
+    #[test]
+    fn spool_directory_is_locked() {
+        let dir = TempDir::new();
+        let spool = EventSpool::open(&dir.0).unwrap();
+        assert!(EventSpool::open(&dir.0).is_err());
+        drop(spool);
+        EventSpool::open(&dir.0).unwrap();
+    }
+
+    #[test]
+    fn spool_rolls_and_deletes_segments() {
+        let dir = TempDir::new();
+        let spool = EventSpool::open_with_segment_size(&dir.0, 1).unwrap();
+        for key in ["a", "b", "c"] {
+            spool.append(&[test_event(key)]).unwrap();
+        }
+        assert!(segment_path(&dir.0, 2).exists());
+        let mut seen = vec![];
+        while let Some((events, next)) = spool.peek().unwrap() {
+            seen.extend(events);
+            spool.commit(next).unwrap();
+        }
+        assert_eq!(keys(&seen), ["a", "b", "c"]);
+        assert!(!segment_path(&dir.0, 0).exists());
+        assert!(!segment_path(&dir.0, 1).exists());
+        assert!(spool.is_empty());
+    }
+
+    #[tokio::test]
+    async fn spool_drains_in_order_once_orb_is_reachable() {
+        let dir = TempDir::new();
+        let spool = EventSpool::open(&dir.0).unwrap();
+        spool.append(&[test_event("a")]).unwrap();
+        spool.append(&[test_event("b")]).unwrap();
+
+        let transport = MockTransport::default();
+        transport.fail(TransportError::new(TransportErrorKind::Connect, "refused"));
+        let client = transport.client();
+        assert!(spool.drain(&client).await.is_err());
+        assert!(!spool.is_empty());
+
+        transport.respond(200, json!({}));
+        transport.respond(
+            200,
+            json!({
+                "validation_failed": [
+                    { "idempotency_key": "b", "validation_errors": ["bad customer"] },
+                ],
+            }),
+        );
+        let summary = spool.drain(&client).await.unwrap();
+        assert_eq!(summary.ingested, 1);
+        assert_eq!(
+            summary.rejected,
+            [RejectedEvent {
+                event: test_event("b"),
+                validation_errors: vec!["bad customer".into()],
+                local_error: None,
+            }]
+        );
+        assert!(spool.is_empty());
+        let bodies: Vec<serde_json::Value> = transport
+            .requests()
+            .iter()
+            .map(|req| serde_json::from_slice(req.body.as_deref().unwrap()).unwrap())
+            .collect();
+        assert_eq!(bodies[1]["events"][0]["idempotency_key"], "a");
+        assert_eq!(bodies[1]["events"][0]["customer_id"], "cust");
+        assert_eq!(bodies[2]["events"][0]["idempotency_key"], "b");
+    }
+
+    #[tokio::test]
+    async fn ingester_drains_past_corrupt_records() {
+        let dir = TempDir::new();
+        let spool = EventSpool::open(&dir.0).unwrap();
+        let mut offsets = vec![];
+        for key in ["a", "b", "c"] {
+            spool.append(&[test_event(key)]).unwrap();
+            offsets.push(spool.state().write_len);
+        }
+        drop(spool);
+
+        // Flip a byte in the payload of the middle record.
+        let path = segment_path(&dir.0, 0);
+        let mut contents = fs::read(&path).unwrap();
+        contents[offsets[0] as usize + HEADER_LEN as usize] ^= 0xFF;
+        fs::write(&path, contents).unwrap();
+
+        let transport = MockTransport::default();
+        transport.respond(200, json!({}));
+        transport.respond(200, json!({}));
+        transport.respond(200, json!({}));
+        let spool = EventSpool::open(&dir.0).unwrap();
+        let ingester = EventIngester::new(transport.client(), IngesterConfig::DEFAULT.spool(spool));
+        ingester.send(test_event("d")).await.unwrap();
+        let err = ingester.flush().await.unwrap_err();
+        assert!(matches!(
+            err.failures.as_slice(),
+            [IngestFailure::Spool(Error::Spool(e))] if e.kind() == io::ErrorKind::InvalidData
+        ));
+
+        // The next flush does not report the corrupt record again.
+        ingester.send(test_event("e")).await.unwrap();
+        ingester.shutdown().await.unwrap();
+        let keys: Vec<serde_json::Value> = transport
+            .requests()
+            .iter()
+            .map(|req| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(req.body.as_deref().unwrap()).unwrap();
+                body["events"][0]["idempotency_key"].clone()
+            })
+            .collect();
+        assert_eq!(keys, ["a", "c", "d", "e"]);
+    }
+
+    #[tokio::test]
+    async fn ingester_spools_while_orb_is_unreachable() {
+        let dir = TempDir::new();
+        let config = IngesterConfig::DEFAULT.retry_policy(RetryPolicy::NEVER);
+
+        let transport = MockTransport::default();
+        transport.fail(TransportError::new(TransportErrorKind::Connect, "refused"));
+        let spool = EventSpool::open(&dir.0).unwrap();
+        let ingester = EventIngester::new(transport.client(), config.clone().spool(spool));
+        ingester.send(test_event("a")).await.unwrap();
+        ingester.shutdown().await.unwrap();
+        assert_eq!(transport.requests().len(), 1);
+
+        // A later process drains the spool before sending new events.
+        let transport = MockTransport::default();
+        transport.respond(200, json!({}));
+        transport.respond(200, json!({}));
+        let spool = EventSpool::open(&dir.0).unwrap();
+        let ingester = EventIngester::new(transport.client(), config.spool(spool.clone()));
+        ingester.send(test_event("b")).await.unwrap();
+        ingester.shutdown().await.unwrap();
+        let keys: Vec<serde_json::Value> = transport
+            .requests()
+            .iter()
+            .map(|req| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(req.body.as_deref().unwrap()).unwrap();
+                body["events"][0]["idempotency_key"].clone()
+            })
+            .collect();
+        assert_eq!(keys, ["a", "b"]);
+        assert!(spool.is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE file at the
+// root of this repository, or online at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Request instrumentation via [`tracing`], when the `tracing` feature is
+//! enabled.
+//!
+//! When the feature is disabled, every operation is a no-op.
+
+use std::future::Future;
+use std::time::Duration;
+
+use reqwest::Method;
+
+use crate::error::{Error, TransportError};
+use crate::transport::HttpResponse;
+
+/// A span that covers all attempts of a single API request.
+#[derive(Debug)]
+pub struct RequestSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+#[cfg(feature = "tracing")]
+impl RequestSpan {
+    pub fn new(method: &Method, template: &str) -> RequestSpan {
+        let span = tracing::info_span!(
+            "orb_request",
+            method = %method,
+            path = template,
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            attempts = tracing::field::Empty,
+            request_id = tracing::field::Empty,
+        );
+        RequestSpan { span }
+    }
+
+    pub fn instrument<F>(&self, fut: F) -> tracing::instrument::Instrumented<F>
+    where
+        F: Future,
+    {
+        tracing::Instrument::instrument(fut, self.span.clone())
+    }
+
+    pub fn record_attempt(
+        &self,
+        attempt: u32,
+        res: &Result<HttpResponse, TransportError>,
+        latency: Duration,
+    ) {
+        self.span.record("attempts", attempt);
+        let latency_ms = latency.as_millis() as u64;
+        match res {
+            Ok(res) => tracing::debug!(
+                attempt,
+                status = res.status.as_u16(),
+                latency_ms,
+                "orb request attempt completed"
+            ),
+            Err(e) => tracing::debug!(
+                attempt,
+                error = %e,
+                latency_ms,
+                "orb request attempt failed"
+            ),
+        }
+    }
+
+    pub fn record_retry(&self, attempt: u32, delay: Duration) {
+        tracing::debug!(
+            attempt,
+            delay_ms = delay.as_millis() as u64,
+            "retrying orb request"
+        );
+    }
+
+    pub fn record_result(
+        &self,
+        res: &Result<HttpResponse, Error>,
+        request_id: Option<&str>,
+        latency: Duration,
+    ) {
+        self.span.record("latency_ms", latency.as_millis() as u64);
+        if let Some(request_id) = request_id {
+            self.span.record("request_id", request_id);
+        }
+        match res {
+            Ok(res) => {
+                self.span.record("status", res.status.as_u16());
+            }
+            Err(e) => {
+                let _guard = self.span.enter();
+                tracing::warn!(error = %e, "orb request failed");
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+impl RequestSpan {
+    pub fn new(_: &Method, _: &str) -> RequestSpan {
+        RequestSpan {}
+    }
+
+    pub fn instrument<F>(&self, fut: F) -> F
+    where
+        F: Future,
+    {
+        fut
+    }
+
+    pub fn record_attempt(&self, _: u32, _: &Result<HttpResponse, TransportError>, _: Duration) {}
+
+    pub fn record_retry(&self, _: u32, _: Duration) {}
+
+    pub fn record_result(&self, _: &Result<HttpResponse, Error>, _: Option<&str>, _: Duration) {}
+}
@@ -0,0 +1,206 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE file at the
+// root of this repository, or online at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode, Url};
+
+use crate::error::TransportError;
+
+/// An HTTP request to be sent by an [`HttpTransport`].
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    /// The request method.
+    pub method: Method,
+    /// The URL to send the request to.
+    pub url: Url,
+    /// The request headers, including the `Authorization` header.
+    pub headers: HeaderMap,
+    /// The request body, if any.
+    pub body: Option<Vec<u8>>,
+    /// The timeout for the request, if it overrides the transport's default.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    pub(crate) fn from_reqwest(req: reqwest::Request) -> HttpRequest {
+        HttpRequest {
+            body: req.body().and_then(|b| b.as_bytes()).map(|b| b.to_vec()),
+            timeout: req.timeout().copied(),
+            method: req.method().clone(),
+            url: req.url().clone(),
+            headers: req.headers().clone(),
+        }
+    }
+}
+
+/// An HTTP response received by an [`HttpTransport`].
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    /// The response status code.
+    pub status: StatusCode,
+    /// The response headers.
+    pub headers: HeaderMap,
+    /// The response body.
+    pub body: Vec<u8>,
+}
+
+/// A mechanism for sending HTTP requests to the Orb API.
+///
+/// Every request made by a [`Client`] is sent through its transport. The
+/// default transport, [`ReqwestTransport`], sends requests with
+/// [`reqwest`]. A custom transport can be installed via
+/// [`ClientBuilder::transport`] to add middleware, to record requests, or to
+/// serve canned responses in tests.
+///
+/// Transports are responsible only for sending a single request and reading
+/// the full response. Retries, rate limits, and error decoding are handled by
+/// the client.
+///
+/// [`Client`]: crate::Client
+/// [`ClientBuilder::transport`]: crate::ClientBuilder::transport
+pub trait HttpTransport: fmt::Debug + Send + Sync + 'static {
+    /// Sends a request and reads the response.
+    ///
+    /// Responses with unsuccessful status codes must be returned as
+    /// responses, not as errors.
+    fn send(&self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, TransportError>>;
+}
+
+/// An [`HttpTransport`] that sends requests with a [`reqwest::Client`].
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// Creates a transport that sends requests with the specified client.
+    pub fn new(client: reqwest::Client) -> ReqwestTransport {
+        ReqwestTransport { client }
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, TransportError>> {
+        Box::pin(async move {
+            let mut request = reqwest::Request::new(req.method, req.url);
+            *request.headers_mut() = req.headers;
+            *request.body_mut() = req.body.map(Into::into);
+            *request.timeout_mut() = req.timeout;
+            let res = self.client.execute(request).await?;
+            Ok(HttpResponse {
+                status: res.status(),
+                headers: res.headers().clone(),
+                body: res.bytes().await?.to_vec(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::{
+        Client, ClientBuilder, ClientConfig, CustomerId, OwnedIngestEventRequest, RetryPolicy,
+    };
+
+    /// A transport that serves canned responses and records the requests it
+    /// receives.
+    #[derive(Debug, Clone, Default)]
+    pub struct MockTransport {
+        responses: Arc<Mutex<VecDeque<Result<HttpResponse, TransportError>>>>,
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+    }
+
+    impl MockTransport {
+        /// Enqueues a response with the specified status and JSON body.
+        pub fn respond(&self, status: u16, body: serde_json::Value) {
+            self.respond_with(HttpResponse {
+                status: StatusCode::from_u16(status).unwrap(),
+                headers: HeaderMap::new(),
+                body: serde_json::to_vec(&body).unwrap(),
+            });
+        }
+
+        /// Enqueues the specified response.
+        pub fn respond_with(&self, res: HttpResponse) {
+            self.responses.lock().unwrap().push_back(Ok(res));
+        }
+
+        /// Enqueues the specified transport error.
+        pub fn fail(&self, e: TransportError) {
+            self.responses.lock().unwrap().push_back(Err(e));
+        }
+
+        /// Returns the requests received so far.
+        pub fn requests(&self) -> Vec<HttpRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        /// Creates a client that sends requests via this transport and
+        /// retries quickly.
+        pub fn client(&self) -> Client {
+            self.client_with(|builder| builder)
+        }
+
+        /// Like [`MockTransport::client`], but further configures the client
+        /// with `configure`.
+        pub fn client_with(
+            &self,
+            configure: impl FnOnce(ClientBuilder) -> ClientBuilder,
+        ) -> Client {
+            let builder = Client::builder()
+                .transport(self.clone())
+                .retry_policy(RetryPolicy::DEFAULT.base_delay(Duration::from_millis(1)));
+            configure(builder)
+                .build(ClientConfig {
+                    api_key: "test".into(),
+                })
+                .unwrap()
+        }
+    }
+
+    /// Returns an event with the specified idempotency key and no properties.
+    pub fn test_event(key: &str) -> OwnedIngestEventRequest {
+        OwnedIngestEventRequest::builder()
+            .idempotency_key(key)
+            .customer_id(CustomerId::Orb("cust"))
+            .event_name("api_call")
+            .timestamp(OffsetDateTime::UNIX_EPOCH)
+            .build()
+            .unwrap()
+    }
+
+    impl HttpTransport for MockTransport {
+        fn send(&self, req: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, TransportError>> {
+            self.requests.lock().unwrap().push(req);
+            let res = self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .expect("mock transport has no more responses");
+            Box::pin(async move { res })
+        }
+    }
+}
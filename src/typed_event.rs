@@ -0,0 +1,401 @@
+// Copyright Materialize, Inc. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE file at the
+// root of this repository, or online at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed usage events.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::Number;
+
+use crate::client::events::{Event, EventPropertyValue, OwnedIngestEventRequest};
+
+/// A usage event with a fixed schema.
+///
+/// An `OrbEvent` converts into an [`OwnedIngestEventRequest`] for ingestion,
+/// and can be recovered from an [`Event`] returned by Orb, e.g., by
+/// [`Client::search_events`](crate::Client::search_events).
+///
+/// With the `derive` feature enabled, `OrbEvent` can be derived for a struct
+/// with named fields. The struct must be annotated with
+/// `#[orb(event_name = "...")]`, and its fields with the following attributes:
+///
+/// * `#[orb(idempotency_key)]` marks the field that holds the idempotency key.
+///   It must convert into and from a `String`.
+/// * `#[orb(customer_id)]` or `#[orb(external_customer_id)]` marks the field
+///   that holds the Orb ID or the external ID of the customer. It must
+///   convert into and from a `String`.
+/// * `#[orb(timestamp)]` marks the `OffsetDateTime` field that holds the
+///   time at which the event occurred.
+/// * `#[orb(rename = "...")]` stores the field in the property with the
+///   given name rather than the name of the field.
+/// * `#[orb(skip)]` omits the field from the event's properties. The field
+///   is set to its default value when converting from an [`Event`].
+///
+/// All other fields are stored as properties, and their types must implement
+/// [`EventProperty`].
+///
+#[cfg_attr(feature = "derive", doc = "```")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use orb_billing::{OrbEvent, OwnedIngestEventRequest};
+/// use time::OffsetDateTime;
+///
+/// #[derive(OrbEvent)]
+/// #[orb(event_name = "api_request")]
+/// struct ApiRequest {
+///     #[orb(idempotency_key)]
+///     id: String,
+///     #[orb(external_customer_id)]
+///     customer: String,
+///     #[orb(timestamp)]
+///     at: OffsetDateTime,
+///     region: String,
+///     #[orb(rename = "bytes")]
+///     response_bytes: u64,
+/// }
+///
+/// let event = OwnedIngestEventRequest::from(ApiRequest {
+///     id: "request-1".into(),
+///     customer: "customer-1".into(),
+///     at: OffsetDateTime::now_utc(),
+///     region: "us-east-1".into(),
+///     response_bytes: 1024,
+/// });
+/// assert_eq!(event.event_name, ApiRequest::EVENT_NAME);
+/// assert_eq!(event.properties.len(), 2);
+/// ```
+pub trait OrbEvent:
+    Into<OwnedIngestEventRequest> + TryFrom<Event, Error = EventConversionError>
+{
+    /// The name of the event.
+    const EVENT_NAME: &'static str;
+}
+
+/// A type that can be stored in a property of an event.
+pub trait EventProperty: Sized {
+    /// Converts the value into a property value.
+    ///
+    /// Returns `None` if the property should be omitted from the event.
+    fn into_property_value(self) -> Option<EventPropertyValue>;
+
+    /// Converts a property value into a value of this type.
+    ///
+    /// Returns `None` if the property value has the wrong type or is out of
+    /// range.
+    fn from_property_value(value: &EventPropertyValue) -> Option<Self>;
+
+    /// Returns the value to use when the property is absent from an event.
+    ///
+    /// The default implementation returns `None`, which indicates that the
+    /// property is required.
+    fn missing_property_value() -> Option<Self> {
+        None
+    }
+
+    /// Extracts the property named `key` from the properties of an event.
+    fn from_properties(
+        properties: &BTreeMap<String, EventPropertyValue>,
+        key: &str,
+    ) -> Result<Self, EventConversionError> {
+        match properties.get(key) {
+            None => Self::missing_property_value().ok_or_else(|| {
+                EventConversionError::MissingProperty {
+                    key: key.to_string(),
+                }
+            }),
+            Some(value) => Self::from_property_value(value).ok_or_else(|| {
+                EventConversionError::InvalidProperty {
+                    key: key.to_string(),
+                    value: value.clone(),
+                }
+            }),
+        }
+    }
+}
+
+impl EventProperty for EventPropertyValue {
+    fn into_property_value(self) -> Option<EventPropertyValue> {
+        Some(self)
+    }
+
+    fn from_property_value(value: &EventPropertyValue) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl EventProperty for String {
+    fn into_property_value(self) -> Option<EventPropertyValue> {
+        Some(EventPropertyValue::String(self))
+    }
+
+    fn from_property_value(value: &EventPropertyValue) -> Option<Self> {
+        match value {
+            EventPropertyValue::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl EventProperty for bool {
+    fn into_property_value(self) -> Option<EventPropertyValue> {
+        Some(EventPropertyValue::Bool(self))
+    }
+
+    fn from_property_value(value: &EventPropertyValue) -> Option<Self> {
+        match value {
+            EventPropertyValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+impl EventProperty for Number {
+    fn into_property_value(self) -> Option<EventPropertyValue> {
+        Some(EventPropertyValue::Number(self))
+    }
+
+    fn from_property_value(value: &EventPropertyValue) -> Option<Self> {
+        match value {
+            EventPropertyValue::Number(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_event_property_int {
+    ($($ty:ty => $as:ident),*) => {
+        $(
+            impl EventProperty for $ty {
+                fn into_property_value(self) -> Option<EventPropertyValue> {
+                    Some(EventPropertyValue::Number(self.into()))
+                }
+
+                fn from_property_value(value: &EventPropertyValue) -> Option<Self> {
+                    match value {
+                        EventPropertyValue::Number(n) => n.$as()?.try_into().ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_event_property_int!(
+    i8 => as_i64, i16 => as_i64, i32 => as_i64, i64 => as_i64, isize => as_i64,
+    u8 => as_u64, u16 => as_u64, u32 => as_u64, u64 => as_u64, usize => as_u64
+);
+
+/// Non-finite values cannot be represented in an event and are omitted.
+impl EventProperty for f64 {
+    fn into_property_value(self) -> Option<EventPropertyValue> {
+        Number::from_f64(self).map(EventPropertyValue::Number)
+    }
+
+    fn from_property_value(value: &EventPropertyValue) -> Option<Self> {
+        match value {
+            EventPropertyValue::Number(n) => n.as_f64(),
+            _ => None,
+        }
+    }
+}
+
+/// `None` omits the property from the event, and an absent property converts
+/// to `None`.
+impl<T: EventProperty> EventProperty for Option<T> {
+    fn into_property_value(self) -> Option<EventPropertyValue> {
+        self.and_then(T::into_property_value)
+    }
+
+    fn from_property_value(value: &EventPropertyValue) -> Option<Self> {
+        T::from_property_value(value).map(Some)
+    }
+
+    fn missing_property_value() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// An error converting an [`Event`] into an [`OrbEvent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EventConversionError {
+    /// The event has a different name.
+    EventName {
+        /// The name of the [`OrbEvent`].
+        expected: &'static str,
+        /// The name of the event.
+        actual: String,
+    },
+    /// The event is associated with a customer that has no external ID.
+    MissingExternalCustomerId,
+    /// A required property is absent from the event.
+    MissingProperty {
+        /// The name of the property.
+        key: String,
+    },
+    /// A property of the event has the wrong type or is out of range.
+    InvalidProperty {
+        /// The name of the property.
+        key: String,
+        /// The value of the property.
+        value: EventPropertyValue,
+    },
+}
+
+impl fmt::Display for EventConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventConversionError::EventName { expected, actual } => {
+                write!(f, "expected event {expected:?}, found {actual:?}")
+            }
+            EventConversionError::MissingExternalCustomerId => {
+                f.write_str("event customer has no external ID")
+            }
+            EventConversionError::MissingProperty { key } => {
+                write!(f, "event is missing property {key:?}")
+            }
+            EventConversionError::InvalidProperty { key, value } => {
+                write!(f, "event property {key:?} has invalid value {value:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EventConversionError {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use orb_billing_derive::OrbEvent;
+    use time::OffsetDateTime;
+
+    use super::{EventConversionError, EventProperty, OrbEvent};
+    use crate::client::customers::OwnedCustomerId;
+    use crate::client::events::{Event, EventPropertyValue, OwnedIngestEventRequest};
+
+    #[derive(Debug, PartialEq, OrbEvent)]
+    #[orb(event_name = "api_request")]
+    struct ApiRequest {
+        #[orb(idempotency_key)]
+        id: String,
+        #[orb(external_customer_id)]
+        customer: String,
+        #[orb(timestamp)]
+        at: OffsetDateTime,
+        region: String,
+        #[orb(rename = "bytes")]
+        response_bytes: u64,
+        cached: bool,
+        latency: Option<f64>,
+        #[orb(skip)]
+        trace: Option<String>,
+    }
+
+    fn request() -> ApiRequest {
+        ApiRequest {
+            id: "key".into(),
+            customer: "cust".into(),
+            at: OffsetDateTime::UNIX_EPOCH,
+            region: "us-east-1".into(),
+            response_bytes: 1024,
+            cached: true,
+            latency: None,
+            trace: None,
+        }
+    }
+
+    #[test]
+    fn derived_event_converts_to_request() {
+        assert_eq!(ApiRequest::EVENT_NAME, "api_request");
+        let event = OwnedIngestEventRequest::from(ApiRequest {
+            trace: Some("ignored".into()),
+            ..request()
+        });
+        assert_eq!(event.idempotency_key, "key");
+        assert_eq!(event.customer_id, OwnedCustomerId::External("cust".into()));
+        assert_eq!(event.event_name, "api_request");
+        assert_eq!(event.timestamp, OffsetDateTime::UNIX_EPOCH);
+        assert_eq!(
+            event.properties,
+            BTreeMap::from([
+                (
+                    "region".into(),
+                    EventPropertyValue::String("us-east-1".into())
+                ),
+                ("bytes".into(), EventPropertyValue::Number(1024.into())),
+                ("cached".into(), EventPropertyValue::Bool(true)),
+            ])
+        );
+    }
+
+    #[test]
+    fn derived_event_converts_from_event() {
+        let ingested = OwnedIngestEventRequest::from(request());
+        let mut event = Event {
+            id: ingested.idempotency_key,
+            customer_id: "orb-cust".into(),
+            external_customer_id: Some("cust".into()),
+            event_name: ingested.event_name,
+            properties: ingested.properties,
+            timestamp: ingested.timestamp,
+        };
+        assert_eq!(ApiRequest::try_from(event.clone()), Ok(request()));
+
+        let mut orb_only = event.clone();
+        orb_only.external_customer_id = None;
+        assert_eq!(
+            ApiRequest::try_from(orb_only),
+            Err(EventConversionError::MissingExternalCustomerId)
+        );
+
+        event
+            .properties
+            .insert("latency".into(), 1.5.into_property_value().unwrap());
+        assert_eq!(
+            ApiRequest::try_from(event.clone()).unwrap().latency,
+            Some(1.5)
+        );
+
+        event
+            .properties
+            .insert("bytes".into(), EventPropertyValue::Number((-1).into()));
+        assert_eq!(
+            ApiRequest::try_from(event.clone()),
+            Err(EventConversionError::InvalidProperty {
+                key: "bytes".into(),
+                value: EventPropertyValue::Number((-1).into()),
+            })
+        );
+
+        event.properties.remove("region");
+        assert_eq!(
+            ApiRequest::try_from(event.clone()),
+            Err(EventConversionError::MissingProperty {
+                key: "region".into()
+            })
+        );
+
+        event.event_name = "other".into();
+        assert_eq!(
+            ApiRequest::try_from(event),
+            Err(EventConversionError::EventName {
+                expected: "api_request",
+                actual: "other".into(),
+            })
+        );
+    }
+}
@@ -14,26 +14,99 @@
 // limitations under the License.
 
 use std::fmt;
-use std::iter;
 
-pub trait StrIteratorExt {
-    fn chain_one<S>(self, s: S) -> Vec<String>
+use crate::error::Error;
+
+/// The path to an API endpoint.
+///
+/// Alongside the path segments, tracks a template for the path in which
+/// resource IDs are replaced with a placeholder, as in `customers/{id}`. The
+/// template identifies the endpoint in traces and metrics without the
+/// unbounded cardinality of resource IDs.
+#[derive(Debug, Clone)]
+pub struct ApiPath {
+    pub segments: Vec<String>,
+    pub template: String,
+}
+
+impl ApiPath {
+    fn push(mut self, segment: String, template: &str) -> ApiPath {
+        if !self.template.is_empty() {
+            self.template.push('/');
+        }
+        self.template.push_str(template);
+        self.segments.push(segment);
+        self
+    }
+}
+
+impl<const N: usize> From<[&'static str; N]> for ApiPath {
+    fn from(segments: [&'static str; N]) -> ApiPath {
+        ApiPath {
+            segments: segments.iter().map(|s| s.to_string()).collect(),
+            template: segments.join("/"),
+        }
+    }
+}
+
+pub trait PathExt {
+    /// Appends a fixed path segment.
+    fn chain_one(self, segment: &'static str) -> ApiPath;
+
+    /// Appends a path segment containing a resource ID.
+    fn chain_id<S>(self, id: S) -> ApiPath
     where
         S: fmt::Display;
 }
 
-impl<T> StrIteratorExt for T
+impl<T> PathExt for T
 where
-    T: IntoIterator,
-    T::Item: AsRef<str>,
+    T: Into<ApiPath>,
 {
-    fn chain_one<S>(self, s: S) -> Vec<String>
+    fn chain_one(self, segment: &'static str) -> ApiPath {
+        self.into().push(segment.into(), segment)
+    }
+
+    fn chain_id<S>(self, id: S) -> ApiPath
     where
         S: fmt::Display,
     {
-        self.into_iter()
-            .map(|s| s.as_ref().into())
-            .chain(iter::once(s.to_string()))
-            .collect()
+        self.into().push(id.to_string(), "{id}")
+    }
+}
+
+/// Returns the value of the required request field `field`, or an error if
+/// it is unset.
+pub fn require_some<T>(value: Option<T>, field: &str) -> Result<T, Error> {
+    value.ok_or_else(|| Error::InvalidRequest {
+        detail: format!("{field} is required"),
+    })
+}
+
+/// Returns an error if the required request field `field` is empty.
+pub fn require_field(value: &str, field: &str) -> Result<(), Error> {
+    if value.is_empty() {
+        return Err(Error::InvalidRequest {
+            detail: format!("{field} is required"),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_template_omits_ids() {
+        let path = ["customers"]
+            .chain_one("external_customer_id")
+            .chain_id("cus/1")
+            .chain_one("credits");
+        assert_eq!(
+            path.segments,
+            ["customers", "external_customer_id", "cus/1", "credits"]
+        );
+        assert_eq!(path.template, "customers/external_customer_id/{id}/credits");
     }
 }
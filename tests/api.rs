@@ -40,7 +40,7 @@ use test_log::test;
 use tokio::time::{self, Duration};
 use tracing::info;
 
-use orb_billing::{AddIncrementCreditLedgerEntryRequestParams, AddVoidCreditLedgerEntryRequestParams, Address, AddressRequest, AmendEventRequest, Client, ClientConfig, CostViewMode, CreateCustomerRequest, CreateSubscriptionRequest, Customer, CustomerCostParams, CustomerCostPriceBlockPrice, CustomerId, CustomerPaymentProviderRequest, Error, Event, EventPropertyValue, EventSearchParams, IngestEventRequest, IngestionMode, InvoiceListParams, LedgerEntry, LedgerEntryRequest, ListParams, PaymentProvider, SubscriptionListParams, TaxId, TaxIdRequest, UpdateCustomerRequest, VoidReason, PlanListParams, CreateBackfillParams};
+use orb_billing::{AddIncrementCreditLedgerEntryRequestParams, AddVoidCreditLedgerEntryRequestParams, Address, AddressRequest, AmendEventRequest, Client, ClientConfig, CostViewMode, CreateCustomerRequest, CreateSubscriptionRequest, Customer, CustomerCostParams, CustomerCostPriceBlockPrice, CustomerId, CustomerPaymentProviderRequest, Error, Event, EventPropertyValue, EventSearchParams, IngestEventRequest, IngestionMode, InvoiceListParams, LedgerEntry, LedgerEntryRequest, ListParams, PaymentProvider, SubscriptionListParams, TaxId, TaxIdRequest, UpdateCustomerRequest, VoidReason, PlanListParams, CreateBackfillParams, BackfillListParams};
 
 /// The API key to authenticate with.
 static API_KEY: Lazy<String> = Lazy::new(|| env::var("ORB_API_KEY").expect("missing ORB_API_KEY"));
@@ -178,7 +178,7 @@ async fn test_customers() {
         .try_collect()
         .await
         .unwrap();
-    assert_eq!(balance.get(0).unwrap().balance, inc_res.ledger.amount);
+    assert_eq!(balance.first().unwrap().balance, inc_res.ledger.amount);
     let ledger_res = client
         .create_ledger_entry(
             &customer.id,
@@ -291,7 +291,7 @@ async fn test_customers() {
             email: email2,
             shipping_address: Some(AddressRequest {
                 city: Some("New York"),
-                country: Some(&CountryCode::US.to_string()),
+                country: Some(CountryCode::US.as_ref()),
                 line1: Some("440 Lafayette St"),
                 line2: Some("Floor 6"),
                 postal_code: Some("10003"),
@@ -299,7 +299,7 @@ async fn test_customers() {
             }),
             billing_address: Some(AddressRequest {
                 city: Some("Boston"),
-                country: Some(&CountryCode::US.to_string()),
+                country: Some(CountryCode::US.as_ref()),
                 ..Default::default()
             }),
             tax_id: Some(TaxIdRequest {
@@ -536,7 +536,7 @@ async fn test_events() {
             .try_collect()
             .await
             .unwrap();
-        if events.get(0).map(|e| e.event_name.clone()) != Some("new test".into()) {
+        if events.first().map(|e| e.event_name.clone()) != Some("new test".into()) {
             info!("  events list not updated after {iteration} attempts.");
             if iteration < MAX_LIST_RETRIES {
                 continue;
@@ -668,7 +668,7 @@ async fn test_create_backfill() {
         timeframe_end: now,
         close_time: None,
         customer_id: None,
-        external_customer_id: None,
+        deprecation_filter: None,
     }).await.unwrap();
     println!("{}", serde_json::to_string(&response).unwrap());
 }
@@ -696,7 +696,7 @@ async fn test_revert_backfill() {
 #[test(tokio::test)]
 async fn test_list_backfill() {
     let client = new_client();
-    let responses = client.list_backfills();
+    let responses = client.list_backfills(&BackfillListParams::default());
     for response in responses.try_collect::<Vec<_>>().await.unwrap() {
         println!("{}", serde_json::to_string(&response).unwrap());
     }
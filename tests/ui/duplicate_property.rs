@@ -0,0 +1,18 @@
+use orb_billing_derive::OrbEvent;
+use time::OffsetDateTime;
+
+#[derive(OrbEvent)]
+#[orb(event_name = "api_request")]
+struct ApiRequest {
+    #[orb(idempotency_key)]
+    id: String,
+    #[orb(external_customer_id)]
+    customer: String,
+    #[orb(timestamp)]
+    at: OffsetDateTime,
+    region: String,
+    #[orb(rename = "region")]
+    zone: String,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use orb_billing_derive::OrbEvent;
+use time::OffsetDateTime;
+
+#[derive(OrbEvent)]
+#[orb(event_name = "api_request")]
+struct ApiRequest {
+    #[orb(idempotency_key)]
+    id: String,
+    #[orb(timestamp)]
+    at: OffsetDateTime,
+    region: String,
+}
+
+fn main() {}